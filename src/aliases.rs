@@ -0,0 +1,200 @@
+//! Canonical spelling of skills
+//!
+//! The same skill is often written in several ways (`postgres`,
+//! `PostgreSQL`), which splits its duration in summaries. Skills are
+//! renamed to their canonical spelling before aggregation and rendering,
+//! and the merged spellings are listed in a [`MergeReport`].
+use crate::source::read_path;
+use crate::Curriculum;
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::Path;
+
+static BUILTIN: &str = include_str!("../data/aliases.json");
+
+/// Canonical spellings of skills, looked up case-insensitively
+#[derive(Debug, Clone, Default)]
+pub struct SkillAliases {
+    /// {lowercase spelling: canonical spelling}
+    canonical: HashMap<String, String>,
+}
+
+impl SkillAliases {
+    /// Aliases of common technologies
+    pub fn builtin() -> Self {
+        let table = serde_json::from_str(BUILTIN).expect("built-in aliases should be valid");
+        let mut aliases = Self::default();
+        aliases.extend(table);
+        aliases
+    }
+
+    /// Add aliases {canonical: [aliases]}, replacing the known ones
+    pub fn extend(&mut self, table: BTreeMap<String, Vec<String>>) {
+        for (canonical, aliases) in table {
+            for alias in aliases.iter().chain([&canonical]) {
+                self.canonical
+                    .insert(alias.to_lowercase(), canonical.clone());
+            }
+        }
+    }
+
+    /// Add aliases read from a JSON, YAML or TOML file {canonical: [aliases]}
+    pub fn extend_from_path(&mut self, path: &Path) -> Result<()> {
+        self.extend(read_path(path)?);
+        Ok(())
+    }
+
+    /// canonical spelling of the skill, if known
+    pub fn get(&self, skill: &str) -> Option<&str> {
+        self.canonical
+            .get(&skill.to_lowercase())
+            .map(String::as_str)
+    }
+
+    /// canonical spelling of the skill, the skill itself if unknown
+    pub fn canonical<'a>(&'a self, skill: &'a str) -> &'a str {
+        self.get(skill).unwrap_or(skill)
+    }
+}
+
+/// Spellings of skills merged by the canonicalization
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MergeReport {
+    /// {canonical spelling: merged spellings}
+    pub merged: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl MergeReport {
+    pub fn is_empty(&self) -> bool {
+        self.merged.is_empty()
+    }
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (canonical, spellings) in &self.merged {
+            let spellings = spellings
+                .iter()
+                .map(|s| format!("`{s}`"))
+                .collect::<Vec<_>>();
+            writeln!(f, "{} merged into `{canonical}`", spellings.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Curriculum {
+    /// Rename skills to their canonical spelling
+    ///
+    /// Skills missing from the aliases are merged case-insensitively, the
+    /// most used spelling being kept.
+    pub fn canonicalize_skills(&mut self, aliases: &SkillAliases) -> MergeReport {
+        // {lowercase spelling: [(spelling, occurrences)]}, in order of appearance
+        let mut spellings: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+        self.for_each_skill_list(&mut |list| {
+            for skill in list.iter().filter(|s| aliases.get(s).is_none()) {
+                let variants = spellings.entry(skill.to_lowercase()).or_default();
+                match variants.iter_mut().find(|(s, _)| s == skill) {
+                    Some((_, n)) => *n += 1,
+                    None => variants.push((skill.clone(), 1)),
+                }
+            }
+        });
+
+        let mut report = MergeReport::default();
+        self.for_each_skill_list(&mut |list| {
+            let mut canonical_list: Vec<String> = Vec::new();
+            for skill in list.drain(..) {
+                let canonical = match aliases.get(&skill) {
+                    Some(canonical) => canonical.to_string(),
+                    // the first of the most used spellings
+                    None => spellings[&skill.to_lowercase()]
+                        .iter()
+                        .rev()
+                        .max_by_key(|(_, n)| n)
+                        .map(|(s, _)| s.clone())
+                        .unwrap_or_else(|| skill.clone()),
+                };
+                if canonical != skill {
+                    report
+                        .merged
+                        .entry(canonical.clone())
+                        .or_default()
+                        .insert(skill);
+                }
+                if !canonical_list.contains(&canonical) {
+                    canonical_list.push(canonical);
+                }
+            }
+            *list = canonical_list;
+        });
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_aliases() {
+        let aliases = SkillAliases::builtin();
+        assert_eq!(aliases.get("postgres"), Some("PostgreSQL"));
+        assert_eq!(aliases.get("postgreSQL"), Some("PostgreSQL"));
+        assert_eq!(aliases.get("Python3"), Some("Python"));
+        assert_eq!(aliases.get("cobol"), None);
+    }
+
+    #[test]
+    fn canonicalize() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [
+                {
+                    "beginning": "2018-01",
+                    "end": "2020-01",
+                    "description": {
+                        "database": ["postgres", "PostgreSQL"],
+                        "programming": ["python3", "COBOL"]
+                    },
+                    "subentries": [
+                        {"description": {"skills": {"mainframe": ["cobol", "Cobol"]}}}
+                    ]
+                },
+                {
+                    "beginning": "2020-01",
+                    "end": "2022-01",
+                    "description": {"programming": ["Python", "cobol", "fortran"]}
+                }
+            ]
+        }
+        "#;
+        let mut cv: Curriculum = serde_json::from_str(data).unwrap();
+        let mut aliases = SkillAliases::builtin();
+        aliases.extend(BTreeMap::from([(
+            "Fortran".to_string(),
+            vec!["f90".to_string()],
+        )]));
+        let report = cv.canonicalize_skills(&aliases);
+
+        let description = cv.experiences[0].description.as_ref().unwrap();
+        assert_eq!(description.database, vec!["PostgreSQL"]);
+        assert_eq!(description.programming, vec!["Python", "cobol"]);
+        let subentry = cv.experiences[0].subentries[0].description.as_ref();
+        assert_eq!(subentry.unwrap().skills["mainframe"], vec!["cobol"]);
+        let skills = cv.get_skills();
+        assert_eq!(skills["programming"]["Python"].calendar.year, 4);
+        assert_eq!(skills["programming"]["Fortran"].calendar.year, 2);
+
+        assert_eq!(
+            report.to_string(),
+            "`fortran` merged into `Fortran`\n\
+             `postgres` merged into `PostgreSQL`\n\
+             `python3` merged into `Python`\n\
+             `COBOL`, `Cobol` merged into `cobol`\n"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Categorization of the skills listed in `other`
+//!
+//! An embedded dictionary of technologies gives the category of well-known
+//! skills, either to suggest a category (lint) or to move the skills.
+use crate::{CVEntry, Curriculum, EntryDescription};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+static BUILTIN: &str = include_str!("../data/technologies.json");
+
+/// category of uncategorized skills
+const OTHER: &str = "other";
+
+/// Categories of well-known technologies
+#[derive(Debug, Clone, Default)]
+pub struct TechnologyDictionary {
+    /// {lowercase skill: category}
+    category: HashMap<String, String>,
+}
+
+impl TechnologyDictionary {
+    /// Dictionary of common technologies
+    pub fn builtin() -> Self {
+        let table = serde_json::from_str(BUILTIN).expect("built-in dictionary should be valid");
+        let mut dictionary = Self::default();
+        dictionary.extend(table);
+        dictionary
+    }
+
+    /// Add technologies {category: [skills]}, replacing the known ones
+    pub fn extend(&mut self, table: BTreeMap<String, Vec<String>>) {
+        for (category, skills) in table {
+            for skill in skills {
+                self.category.insert(skill.to_lowercase(), category.clone());
+            }
+        }
+    }
+
+    /// category of the skill, if known
+    pub fn category(&self, skill: &str) -> Option<&str> {
+        self.category.get(&skill.to_lowercase()).map(String::as_str)
+    }
+}
+
+/// Skill listed in `other` whose category is known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategorySuggestion {
+    /// path of the description, e.g. `experiences[0].subentries[1].description`
+    pub path: String,
+    pub skill: String,
+    pub category: String,
+}
+
+impl fmt::Display for CategorySuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: `{}` belongs to category `{}`",
+            self.path, self.skill, self.category
+        )
+    }
+}
+
+impl EntryDescription {
+    /// skills listed in `other`, from the field or the skills map
+    fn uncategorized(&self) -> impl Iterator<Item = &String> {
+        self.other
+            .iter()
+            .chain(self.skills.get(OTHER).into_iter().flatten())
+    }
+
+    /// move the skill from `other` to the category
+    fn move_skill(&mut self, skill: &str, category: &str) {
+        self.other.retain(|s| s != skill);
+        if let Some(other) = self.skills.get_mut(OTHER) {
+            other.retain(|s| s != skill);
+        }
+        self.skills.retain(|_, skills| !skills.is_empty());
+        let list = match category {
+            "programming" => &mut self.programming,
+            "version" => &mut self.version,
+            "database" => &mut self.database,
+            "cloud" => &mut self.cloud,
+            "ci" => &mut self.ci,
+            _ => self.skills.entry(category.into()).or_default(),
+        };
+        // other spellings are kept, merging them is up to --canonicalize
+        if !list.iter().any(|s| s == skill) {
+            list.push(skill.into());
+        }
+    }
+}
+
+impl CVEntry {
+    /// suggestions for the entry and its subentries
+    fn suggest_categories(
+        &self,
+        path: &str,
+        dictionary: &TechnologyDictionary,
+        suggestions: &mut Vec<CategorySuggestion>,
+    ) {
+        if let Some(desc) = &self.description {
+            for skill in desc.uncategorized() {
+                if let Some(category) = dictionary.category(skill) {
+                    suggestions.push(CategorySuggestion {
+                        path: format!("{path}.description"),
+                        skill: skill.clone(),
+                        category: category.into(),
+                    });
+                }
+            }
+        }
+        for (idx, subentry) in self.subentries.iter().enumerate() {
+            subentry.suggest_categories(
+                &format!("{path}.subentries[{idx}]"),
+                dictionary,
+                suggestions,
+            );
+        }
+    }
+
+    /// move skills of the entry and its subentries to their category
+    fn categorize_skills(&mut self, dictionary: &TechnologyDictionary) {
+        if let Some(desc) = &mut self.description {
+            let known = desc
+                .uncategorized()
+                .filter_map(|skill| Some((skill.clone(), dictionary.category(skill)?)))
+                .collect::<Vec<_>>();
+            for (skill, category) in known {
+                desc.move_skill(&skill, category);
+            }
+        }
+        for subentry in &mut self.subentries {
+            subentry.categorize_skills(dictionary);
+        }
+    }
+}
+
+impl Curriculum {
+    /// Find skills listed in `other` whose category is known
+    pub fn suggest_categories(&self, dictionary: &TechnologyDictionary) -> Vec<CategorySuggestion> {
+        let mut suggestions = Vec::new();
+        for (section, entries) in [
+            ("education", &self.education),
+            ("experiences", &self.experiences),
+        ] {
+            for (idx, entry) in entries.iter().enumerate() {
+                entry.suggest_categories(
+                    &format!("{section}[{idx}]"),
+                    dictionary,
+                    &mut suggestions,
+                );
+            }
+        }
+        suggestions
+    }
+
+    /// Move skills listed in `other` to their category, returning the moves
+    pub fn categorize_skills(
+        &mut self,
+        dictionary: &TechnologyDictionary,
+    ) -> Vec<CategorySuggestion> {
+        let suggestions = self.suggest_categories(dictionary);
+        for entry in self.education.iter_mut().chain(&mut self.experiences) {
+            entry.categorize_skills(dictionary);
+        }
+        suggestions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dictionary() {
+        let dictionary = TechnologyDictionary::builtin();
+        assert_eq!(dictionary.category("Kafka"), Some("messaging"));
+        assert_eq!(dictionary.category("terraform"), Some("cloud"));
+        assert_eq!(dictionary.category("PostgreSQL"), Some("database"));
+        assert_eq!(dictionary.category("excel"), None);
+    }
+
+    #[test]
+    fn categorize() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [
+                {
+                    "description": {
+                        "cloud": ["aws"],
+                        "other": ["kafka", "terraform", "excel", "AWS"]
+                    },
+                    "subentries": [
+                        {"description": {"skills": {"other": ["Prometheus"]}}}
+                    ]
+                }
+            ]
+        }
+        "#;
+        let mut cv: Curriculum = serde_json::from_str(data).unwrap();
+        let dictionary = TechnologyDictionary::builtin();
+        let lint = cv
+            .suggest_categories(&dictionary)
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lint,
+            vec![
+                "experiences[0].description: `kafka` belongs to category `messaging`",
+                "experiences[0].description: `terraform` belongs to category `cloud`",
+                "experiences[0].description: `AWS` belongs to category `cloud`",
+                "experiences[0].subentries[0].description: `Prometheus` belongs to category `observability`",
+            ]
+        );
+
+        let moved = cv.categorize_skills(&dictionary);
+        assert_eq!(moved.len(), 4);
+        assert!(cv.suggest_categories(&dictionary).is_empty());
+        let description = cv.experiences[0].description.as_ref().unwrap();
+        assert_eq!(description.other, vec!["excel"]);
+        assert_eq!(description.cloud, vec!["aws", "terraform", "AWS"]);
+        assert_eq!(description.skills["messaging"], vec!["kafka"]);
+        let subentry = cv.experiences[0].subentries[0].description.as_ref();
+        let skills = &subentry.unwrap().skills;
+        assert_eq!(skills.keys().collect::<Vec<_>>(), vec!["observability"]);
+    }
+}
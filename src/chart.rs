@@ -0,0 +1,134 @@
+//! Bar chart of the most used skills in the LaTeX output
+use crate::{escape_latex, Curriculum, LatexOptions, SkillSort};
+use std::collections::BTreeSet;
+
+/// colors of the categories, in display order
+const CATEGORY_COLORS: &[&str] = &[
+    "color1", "orange", "teal", "violet", "olive", "purple", "brown", "magenta",
+];
+
+impl Curriculum {
+    /// create the pgfplots chart of the most used skills
+    ///
+    /// Skills are grouped by category, in display order, with their
+    /// calendar duration in years.
+    pub(crate) fn skill_chart_to_latex(
+        &self,
+        options: &LatexOptions,
+        tags: &mut BTreeSet<String>,
+    ) -> String {
+        let mut report = self.skill_report(&options.skill_options());
+        report.sort(SkillSort::Duration, self);
+        report.rows.truncate(options.chart_skills);
+        report.sort(SkillSort::Category, self);
+        if report.rows.is_empty() {
+            return "".into();
+        }
+        tags.insert("skillchart".into());
+
+        let labels = report
+            .rows
+            .iter()
+            .map(|row| format!("{{{}}}", escape_latex(&row.skill)))
+            .collect::<Vec<_>>();
+        let mut lines: Vec<String> = Vec::new();
+        lines.push("% ---- begin skill chart".into());
+        lines.push("\\ifskillchart%".into());
+        lines.push("\\begin{center}".into());
+        lines.push("\\begin{tikzpicture}".into());
+        lines.push("\\begin{axis}[".into());
+        lines.push("    xbar, xmin=0, bar width=6pt, bar shift=0pt,".into());
+        lines.push(format!(
+            "    width=0.8\\linewidth, height={:.1}cm,",
+            0.5 * labels.len() as f64 + 1.5
+        ));
+        lines.push(format!(
+            "    ytick={{0,...,{}}}, y dir=reverse, yticklabel style={{font=\\small}},",
+            labels.len() - 1
+        ));
+        lines.push(format!("    yticklabels={{{}}},", labels.join(",")));
+        lines.push("    xlabel={years}, legend style={font=\\tiny}, legend pos=south east,".into());
+        lines.push(
+            "    nodes near coords, every node near coord/.append style={font=\\tiny},".into(),
+        );
+        lines.push("    /pgf/number format/fixed, /pgf/number format/precision=1,".into());
+        lines.push("]".into());
+        let mut idx = 0;
+        let categories = report.rows.chunk_by(|r1, r2| r1.category == r2.category);
+        for (nth, rows) in categories.enumerate() {
+            let coordinates = rows
+                .iter()
+                .map(|row| {
+                    let coordinate = format!("({:.2},{idx})", row.duration.as_years());
+                    idx += 1;
+                    coordinate
+                })
+                .collect::<Vec<_>>();
+            lines.push(format!(
+                "\\addplot[fill={}, draw=none] coordinates {{{}}};",
+                CATEGORY_COLORS[nth % CATEGORY_COLORS.len()],
+                coordinates.join(" ")
+            ));
+            lines.push(format!(
+                "\\addlegendentry{{{}}}",
+                escape_latex(&rows[0].category)
+            ));
+        }
+        lines.push("\\end{axis}".into());
+        lines.push("\\end{tikzpicture}".into());
+        lines.push("\\end{center}".into());
+        lines.push("\\fi% ---- end   skill chart".into());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn skill_chart() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [
+                {
+                    "beginning": "2015-01",
+                    "end": "2018-07",
+                    "description": {"programming": ["python", "C#"], "ci": ["git"]}
+                },
+                {
+                    "beginning": "2018-07",
+                    "end": "2020-01",
+                    "description": {"programming": ["python"], "database": ["sqlite"]}
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let options = LatexOptions {
+            chart_skills: 3,
+            today: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            ..Default::default()
+        };
+        let mut tags = BTreeSet::new();
+        let tex = cv.skill_chart_to_latex(&options, &mut tags);
+        assert!(tex.contains("    yticklabels={{python},{C\\#},{git}},\n"));
+        assert!(tex.contains(
+            "\\addplot[fill=color1, draw=none] coordinates {(5.00,0) (3.50,1)};\n\
+             \\addlegendentry{programming languages}\n\
+             \\addplot[fill=orange, draw=none] coordinates {(3.50,2)};\n\
+             \\addlegendentry{CI/CD}\n"
+        ));
+        assert!(!tex.contains("sqlite"));
+        assert_eq!(tags, BTreeSet::from(["skillchart".into()]));
+
+        let options = LatexOptions {
+            chart_skills: 0,
+            ..options
+        };
+        assert!(cv.skill_chart_to_latex(&options, &mut tags).is_empty());
+    }
+}
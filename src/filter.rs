@@ -0,0 +1,281 @@
+//! Selection and ordering of entries before rendering
+//!
+//! Unlike the conditional tags of the LaTeX output, filtered entries are
+//! absent from every output.
+use crate::{reference_date, CVEntry, Curriculum, Interval, SkillAliases};
+use chrono::{DateTime, Utc};
+use std::cmp::Reverse;
+
+/// Criteria selecting the entries to keep
+///
+/// Experiences are kept if they match every given criterion. Education
+/// entries are only removed by the exclusions.
+#[derive(Debug, Clone)]
+pub struct EntryFilter {
+    /// entries ending before this date are removed
+    pub since: Option<DateTime<Utc>>,
+    /// entries beginning after this date are removed
+    pub until: Option<DateTime<Utc>>,
+    /// entries of other institutions are removed
+    pub institutions: Vec<String>,
+    /// entries of these institutions are removed
+    pub exclude_institutions: Vec<String>,
+    /// entries using none of these skills are removed
+    pub skills: Vec<String>,
+    /// aliases of the skills, compared by canonical spelling
+    pub aliases: SkillAliases,
+    /// entries having none of these tags are removed
+    pub tags: Vec<String>,
+    /// entries having one of these tags are removed
+    pub exclude_tags: Vec<String>,
+    /// entries of lower priority are removed
+    pub min_priority: Option<i32>,
+    /// end of the ongoing entries
+    pub today: DateTime<Utc>,
+}
+
+impl Default for EntryFilter {
+    /// all entries kept
+    fn default() -> Self {
+        Self {
+            since: None,
+            until: None,
+            institutions: Vec::new(),
+            exclude_institutions: Vec::new(),
+            skills: Vec::new(),
+            aliases: SkillAliases::default(),
+            tags: Vec::new(),
+            exclude_tags: Vec::new(),
+            min_priority: None,
+            today: reference_date(),
+        }
+    }
+}
+
+/// whether the name is in the list, ignoring case
+fn contains(list: &[String], name: &str) -> bool {
+    list.iter().any(|n| n.eq_ignore_ascii_case(name))
+}
+
+impl EntryFilter {
+    /// the entry is excluded or out of the date range
+    fn rejects(&self, entry: &CVEntry, span: Option<Interval>) -> bool {
+        let out_of_range = span.is_some_and(|(beginning, end)| {
+            self.since.is_some_and(|since| end < since)
+                || self.until.is_some_and(|until| beginning > until)
+        });
+        out_of_range
+            || contains(&self.exclude_institutions, &entry.institution.to_plain())
+            || entry.tags.iter().any(|t| contains(&self.exclude_tags, t))
+    }
+
+    /// the entry uses one of the skills, compared by canonical spelling
+    fn uses_skills(&self, entry: &CVEntry) -> bool {
+        let wanted = self
+            .skills
+            .iter()
+            .map(|s| self.aliases.canonical(s).to_string())
+            .collect::<Vec<_>>();
+        let skills = entry.extract_skills();
+        skills
+            .values()
+            .flatten()
+            .any(|s| contains(&wanted, self.aliases.canonical(s)))
+    }
+
+    /// the entry matches the institutions, skills, tags and priority
+    fn selects(&self, entry: &CVEntry) -> bool {
+        self.min_priority.is_none_or(|min| entry.priority >= min)
+            && (self.institutions.is_empty()
+                || contains(&self.institutions, &entry.institution.to_plain()))
+            && (self.skills.is_empty() || self.uses_skills(entry))
+            && (self.tags.is_empty() || entry.tags.iter().any(|t| contains(&self.tags, t)))
+    }
+
+    /// remove the subentries not kept, return whether the entry is kept
+    ///
+    /// Entries not matching are kept if some of their subentries match.
+    fn retain(&self, entry: &mut CVEntry, parent: Option<Interval>) -> bool {
+        let span = entry.span_within(self.today, parent);
+        if self.rejects(entry, span) {
+            return false;
+        }
+        if self.selects(entry) {
+            // subentries are only removed by the dates and exclusions
+            let all = Self {
+                institutions: Vec::new(),
+                skills: Vec::new(),
+                tags: Vec::new(),
+                min_priority: None,
+                ..self.clone()
+            };
+            entry.subentries.retain_mut(|sub| all.retain(sub, span));
+            return true;
+        }
+        entry.subentries.retain_mut(|sub| self.retain(sub, span));
+        !entry.subentries.is_empty()
+    }
+}
+
+impl Curriculum {
+    /// Remove the entries not selected by the filter
+    pub fn filter_entries(&mut self, filter: &EntryFilter) {
+        self.experiences.retain_mut(|xp| filter.retain(xp, None));
+        let exclusions = EntryFilter {
+            exclude_institutions: filter.exclude_institutions.clone(),
+            exclude_tags: filter.exclude_tags.clone(),
+            today: filter.today,
+            ..Default::default()
+        };
+        self.education
+            .retain_mut(|edu| exclusions.retain(edu, None));
+    }
+
+    /// Sort experiences and their subentries by decreasing priority
+    ///
+    /// Entries of the same priority keep their order.
+    pub fn sort_by_priority(&mut self) {
+        fn sort(entries: &mut [CVEntry]) {
+            entries.sort_by_key(|e| Reverse(e.priority));
+            for entry in entries {
+                sort(&mut entry.subentries);
+            }
+        }
+        sort(&mut self.experiences);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn curriculum() -> Curriculum {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [
+                {"beginning": "2005", "end": "2010", "institution": "Bennett-Wallace"}
+            ],
+            "experiences": [
+                {
+                    "beginning": "2010-01",
+                    "end": "2014-01",
+                    "institution": "Brewer LLC",
+                    "description": {"programming": ["java"]}
+                },
+                {
+                    "beginning": "2014-01",
+                    "end": "2022-01",
+                    "institution": "Lee & Sons",
+                    "tags": ["consulting"],
+                    "subentries": [
+                        {
+                            "beginning": "2014-01",
+                            "end": "2018-01",
+                            "institution": "client A",
+                            "tags": ["public-sector"],
+                            "description": {"programming": ["Rust"]}
+                        },
+                        {
+                            "beginning": "2018-01",
+                            "institution": "client B",
+                            "priority": 2,
+                            "description": {"programming": ["python"]}
+                        }
+                    ]
+                }
+            ]
+        }
+        "#;
+        serde_json::from_str(data).unwrap()
+    }
+
+    fn institutions(entries: &[CVEntry]) -> Vec<String> {
+        entries
+            .iter()
+            .flat_map(|e| {
+                std::iter::once(e.institution.to_string()).chain(institutions(&e.subentries))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn filter_dates() {
+        let mut cv = curriculum();
+        cv.filter_entries(&EntryFilter {
+            since: Some(Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).unwrap()),
+            ..Default::default()
+        });
+        assert_eq!(
+            institutions(&cv.experiences),
+            vec!["Lee & Sons", "client B"]
+        );
+        assert_eq!(cv.education.len(), 1);
+    }
+
+    #[test]
+    fn filter_skills_and_tags() {
+        let mut cv = curriculum();
+        cv.filter_entries(&EntryFilter {
+            skills: vec!["rust".into()],
+            ..Default::default()
+        });
+        assert_eq!(
+            institutions(&cv.experiences),
+            vec!["Lee & Sons", "client A"]
+        );
+
+        let mut cv = curriculum();
+        cv.filter_entries(&EntryFilter {
+            skills: vec!["python3".into()],
+            aliases: SkillAliases::builtin(),
+            ..Default::default()
+        });
+        assert_eq!(
+            institutions(&cv.experiences),
+            vec!["Lee & Sons", "client B"]
+        );
+
+        let mut cv = curriculum();
+        cv.filter_entries(&EntryFilter {
+            tags: vec!["consulting".into()],
+            exclude_tags: vec!["public-sector".into()],
+            ..Default::default()
+        });
+        assert_eq!(
+            institutions(&cv.experiences),
+            vec!["Lee & Sons", "client B"]
+        );
+
+        let mut cv = curriculum();
+        cv.filter_entries(&EntryFilter {
+            exclude_institutions: vec!["brewer llc".into(), "Bennett-Wallace".into()],
+            ..Default::default()
+        });
+        assert_eq!(
+            institutions(&cv.experiences),
+            vec!["Lee & Sons", "client A", "client B"]
+        );
+        assert!(cv.education.is_empty());
+    }
+
+    #[test]
+    fn priority() {
+        let mut cv = curriculum();
+        cv.sort_by_priority();
+        assert_eq!(
+            institutions(&cv.experiences),
+            vec!["Brewer LLC", "Lee & Sons", "client B", "client A"]
+        );
+
+        cv.filter_entries(&EntryFilter {
+            min_priority: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(
+            institutions(&cv.experiences),
+            vec!["Lee & Sons", "client B"]
+        );
+    }
+}
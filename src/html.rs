@@ -0,0 +1,341 @@
+//! HTML rendering of the curriculum
+//!
+//! The generated page is self-contained: the stylesheet is embedded
+//! and sections follow the LaTeX output.
+use crate::{CVEmail, CVEntry, CVLanguage, Curriculum, EntryDescription, PersonalData};
+use crate::{List, SkillCategory};
+
+static STYLE: &str = include_str!("../data/style.css");
+
+/// escape HTML special characters
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// transform text with multiple paragraph in HTML
+fn format_long_text(text: &str) -> String {
+    escape_html(text).replace('\n', "<br>\n")
+}
+
+/// Get HTML for small paragraph to be inserted in job description
+fn get_titled_description(title: &str, content: &str) -> String {
+    let mut lines = Vec::new();
+    lines.push("<div class=\"titled\">".into());
+    lines.push(format!("<h4>{}</h4>", escape_html(title)));
+    lines.push(content.into());
+    lines.push("</div>".into());
+    lines.join("\n")
+}
+
+impl Curriculum {
+    /// Generate the HTML corresponding to the whole document
+    pub fn to_html(&self) -> String {
+        let mut output = Vec::new();
+        output.push("<!DOCTYPE html>".into());
+        output.push("<html>".into());
+        output.push("<head>".into());
+        output.push("<meta charset=\"utf-8\">".into());
+        output.push(format!(
+            "<title>{}</title>",
+            escape_html(&self.personal_data.name)
+        ));
+        output.push("<style>".into());
+        output.push(STYLE.trim_end().into());
+        output.push("</style>".into());
+        output.push("</head>".into());
+        output.push("<body>".into());
+
+        let categories = self.skill_categories();
+        output.push(self.personal_data.to_html());
+
+        output.push("<section class=\"education\">".into());
+        output.push("<h2>Education</h2>".into());
+        for edu in &self.education {
+            output.push(edu.to_html(&categories));
+        }
+        output.push("</section>".into());
+
+        output.push("<section class=\"experiences\">".into());
+        output.push("<h2>Professional experience</h2>".into());
+        for experience in &self.experiences {
+            output.push(experience.to_html(&categories));
+        }
+        output.push("</section>".into());
+
+        output.push("<section class=\"languages\">".into());
+        output.push("<h2>Languages</h2>".into());
+        output.push("<table class=\"languages\">".into());
+        for language in &self.languages {
+            output.push(language.to_html());
+        }
+        output.push("</table>".into());
+        output.push("</section>".into());
+
+        output.push("</body>".into());
+        output.push("</html>".into());
+        output.join("\n")
+    }
+}
+
+impl CVEntry {
+    fn to_html(&self, categories: &[SkillCategory]) -> String {
+        let mut lines = Vec::new();
+        lines.push("<div class=\"cventry\">".into());
+        lines.push(format!(
+            "<div class=\"dates\">{}</div>",
+            self.get_dates().replace("--", "&ndash;")
+        ));
+        lines.push("<div class=\"content\">".into());
+
+        let mut heading = Vec::new();
+        if !self.degree.is_empty() {
+            heading.push(format!(
+                "<span class=\"degree\">{}</span>",
+                escape_html(&self.degree.to_plain())
+            ));
+        }
+        if !self.institution.is_empty() {
+            heading.push(format!(
+                "<span class=\"institution\">{}</span>",
+                escape_html(&self.institution.to_plain())
+            ));
+        }
+        if let Some(city) = &self.city {
+            heading.push(format!(
+                "<span class=\"city\">{}</span>",
+                escape_html(&city.to_plain())
+            ));
+        }
+        if let Some(grade) = &self.grade {
+            heading.push(format!(
+                "<span class=\"grade\">{}</span>",
+                escape_html(&grade.to_plain())
+            ));
+        }
+        lines.push(format!(
+            "<div class=\"heading\">{}</div>",
+            heading.join(", ")
+        ));
+
+        if let Some(d) = &self.description {
+            lines.push(d.to_html(categories));
+        }
+        if !self.subentries.is_empty() {
+            lines.push("<div class=\"subentries\">".into());
+            for subentry in &self.subentries {
+                lines.push(subentry.to_html(categories));
+            }
+            lines.push("</div>".into());
+        }
+        lines.push("</div>".into());
+        lines.push("</div>".into());
+        lines.join("\n")
+    }
+}
+
+impl EntryDescription {
+    fn to_html(&self, categories: &[SkillCategory]) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        lines.push("<div class=\"description\">".into());
+        if !self.context.is_empty() {
+            lines.push(format!(
+                "<p class=\"context\">{}</p>",
+                format_long_text(&self.context.to_plain())
+            ));
+        }
+        if !&self.achievements.is_empty() {
+            lines.push(get_titled_description(
+                "Achievements",
+                &List(self.achievements.clone()).to_html(),
+            ));
+        }
+        if !self.team.is_empty() {
+            lines.push(get_titled_description(
+                "Team",
+                &format!("<p>{}</p>", format_long_text(&self.team.to_plain())),
+            ));
+        }
+        if !&self.tasks.is_empty() {
+            lines.push(get_titled_description(
+                "Tasks",
+                &List(self.tasks.clone()).to_html(),
+            ));
+        }
+        let skills = &self.extract_skills();
+        if !skills.is_empty() {
+            let mut techno = Vec::new();
+            techno.push("<dl class=\"skills\">".into());
+            for category in categories {
+                if let Some(list) = skills.get(category.name.as_str()) {
+                    techno.push(format!(
+                        "<dt>{}</dt><dd>{}</dd>",
+                        escape_html(category.display_name()),
+                        escape_html(&list.join(", "))
+                    ));
+                }
+            }
+            techno.push("</dl>".into());
+            lines.push(get_titled_description(
+                "Technical environnement",
+                &techno.join("\n"),
+            ));
+        }
+        lines.push("</div>".into());
+        lines.join("\n")
+    }
+}
+
+impl CVEmail {
+    fn to_html(&self) -> String {
+        let mail = escape_html(&self.mail);
+        let link = format!("<a href=\"mailto:{mail}\">{mail}</a>");
+        if let Some(name) = &self.name {
+            format!("{}: {}", escape_html(name), link)
+        } else {
+            link
+        }
+    }
+}
+
+impl PersonalData {
+    fn to_html(&self) -> String {
+        let mut lines = Vec::new();
+        lines.push("<header>".into());
+        lines.push(format!("<h1>{}</h1>", escape_html(&self.name)));
+        if let Some(title) = &self.title {
+            lines.push(format!("<p class=\"title\">{}</p>", escape_html(title)));
+        }
+        let mut contacts = Vec::new();
+        for t in &self.mobile {
+            contacts.push(format!("&#9990; {}", escape_html(t)));
+        }
+        for e in &self.email {
+            contacts.push(e.to_html());
+        }
+        // socials
+        let socials = [
+            ("github", "https://github.com/", &self.github),
+            ("gitlab", "https://gitlab.com/", &self.gitlab),
+            ("linkedin", "https://www.linkedin.com/in/", &self.linkedin),
+            ("twitter", "https://twitter.com/", &self.twitter),
+        ];
+        for (name, base, account) in socials {
+            if let Some(account) = account {
+                let account = escape_html(account);
+                contacts.push(format!("{name}: <a href=\"{base}{account}\">{account}</a>"));
+            }
+        }
+        for (n, u) in &self.webpage {
+            contacts.push(format!(
+                "{}: <a href=\"{u}\">{u}</a>",
+                escape_html(n),
+                u = escape_html(u)
+            ));
+        }
+        if !contacts.is_empty() {
+            lines.push("<ul class=\"contact\">".into());
+            for contact in contacts {
+                lines.push(format!("<li>{contact}</li>"));
+            }
+            lines.push("</ul>".into());
+        }
+        lines.push("</header>".into());
+        lines.join("\n")
+    }
+}
+
+impl CVLanguage {
+    fn to_html(&self) -> String {
+        format!(
+            "<tr><td class=\"language\">{}</td><td class=\"level\">{}</td><td class=\"comment\">{}</td></tr>",
+            escape_html(&self.language),
+            escape_html(&self.level),
+            escape_html(&self.comment)
+        )
+    }
+}
+
+impl List {
+    fn to_html(&self) -> String {
+        let mut lines = Vec::new();
+        lines.push("<ul>".into());
+        for elt in &self.0 {
+            lines.push(format!("<li>{}</li>", escape_html(&elt.to_plain())));
+        }
+        lines.push("</ul>".to_string());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape() {
+        assert_eq!(
+            escape_html("Campbell, Delgado & <Parker>"),
+            "Campbell, Delgado &amp; &lt;Parker&gt;"
+        );
+    }
+
+    #[test]
+    fn subentries_html() {
+        let data = r#"
+        {
+            "beginning": "1977-07-01",
+            "end": "2000-11-25",
+            "institution": "Campbell, Delgado & Parker",
+            "description": {
+                "context": "some\ncontext",
+                "ci": ["git", "gitlab"]
+            },
+            "subentries": [
+                {
+                    "beginning": "1977-07-01",
+                    "end": "1980-07-01",
+                    "description": {
+                        "tasks": ["first task", "second task"]
+                    }
+                }
+            ]
+        }
+        "#;
+        let entry: CVEntry = serde_json::from_str(data).unwrap();
+        let html = entry.to_html(&SkillCategory::defaults());
+        assert_eq!(html.matches("<div").count(), html.matches("</div>").count());
+        assert_eq!(html.matches("class=\"cventry\"").count(), 2);
+        assert!(html.contains("Campbell, Delgado &amp; Parker"));
+        assert!(html.contains("some<br>\ncontext"));
+        assert!(html.contains("<dt>CI/CD</dt><dd>git, gitlab</dd>"));
+        assert!(html.contains("<li>second task</li>"));
+    }
+
+    #[test]
+    fn write_personal_data() {
+        let data = r#"
+        {
+            "name": "Jessica Meyer",
+            "title": "Environmental manager",
+            "email": [{"name": "work", "mail": "nom@example.com"}],
+            "github": "example",
+            "webpage": [["example", "www.example.com"]]
+        }"#;
+        let personal_data: PersonalData = serde_json::from_str(data).unwrap();
+        let html = personal_data.to_html();
+        assert!(html.contains("<h1>Jessica Meyer</h1>"));
+        assert!(html.contains("work: <a href=\"mailto:nom@example.com\">"));
+        assert!(html.contains("href=\"https://github.com/example\""));
+    }
+}
@@ -0,0 +1,693 @@
+//! Conversion from and to the [JSON Resume](https://jsonresume.org/schema) schema
+//!
+//! Fields without any counterpart in the other schema are not silently
+//! dropped: they are listed in a [`ConversionReport`].
+use crate::{
+    CVDate, CVEmail, CVEntry, CVLanguage, Curriculum, EntryDescription, PersonalData, Text,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// social networks having a dedicated field in [`PersonalData`]
+const NETWORKS: &[&str] = &["github", "gitlab", "linkedin", "twitter"];
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct JsonResume {
+    #[serde(default)]
+    pub basics: Basics,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub work: Vec<Work>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub education: Vec<Education>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<Skill>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<Language>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Basics {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<Profile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Profile {
+    #[serde(default)]
+    pub network: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Work {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Education {
+    #[serde(default)]
+    pub institution: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub study_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub courses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Skill {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Language {
+    #[serde(default)]
+    pub language: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fluency: Option<String>,
+}
+
+/// Fields that could not be mapped during a conversion
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConversionReport {
+    /// path of each field, e.g. `experiences[1].description.tasks`
+    pub unmapped: Vec<String>,
+}
+
+impl ConversionReport {
+    pub fn is_empty(&self) -> bool {
+        self.unmapped.is_empty()
+    }
+
+    fn push(&mut self, path: String) {
+        self.unmapped.push(path);
+    }
+}
+
+impl fmt::Display for ConversionReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for path in &self.unmapped {
+            writeln!(f, "{path} could not be mapped")?;
+        }
+        Ok(())
+    }
+}
+
+/// JSON Resume date, ongoing being a missing date
+fn format_date(date: &Option<CVDate>) -> Option<String> {
+    match date {
+        Some(CVDate::Present) | None => None,
+        Some(date) => Some(date.to_string()),
+    }
+}
+
+/// parse a JSON Resume date, reporting it if invalid
+fn parse_date(
+    date: &Option<String>,
+    path: String,
+    report: &mut ConversionReport,
+) -> Option<CVDate> {
+    let parsed = date.as_ref()?.parse().ok();
+    if parsed.is_none() {
+        report.push(path);
+    }
+    parsed
+}
+
+/// non-empty string
+fn non_empty(s: &str) -> Option<String> {
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+/// skills of the entry and its subentries, in order of appearance
+/// {category: [skills]}
+fn collect_skills<'a>(entry: &'a CVEntry, acc: &mut HashMap<&'a str, Vec<String>>) {
+    for (category, skills) in entry.extract_skills() {
+        let known = acc.entry(category).or_default();
+        for skill in skills {
+            if !known.contains(&skill) {
+                known.push(skill);
+            }
+        }
+    }
+    for subentry in &entry.subentries {
+        collect_skills(subentry, acc);
+    }
+}
+
+impl Curriculum {
+    /// Convert to JSON Resume
+    pub fn to_json_resume(&self) -> (JsonResume, ConversionReport) {
+        let mut report = ConversionReport::default();
+        let basics = self.personal_data.to_json_resume(&mut report);
+        let work = self
+            .experiences
+            .iter()
+            .enumerate()
+            .map(|(idx, xp)| xp.to_json_resume_work(&format!("experiences[{idx}]"), &mut report))
+            .collect();
+        let education = self
+            .education
+            .iter()
+            .enumerate()
+            .map(|(idx, edu)| {
+                edu.to_json_resume_education(&format!("education[{idx}]"), &mut report)
+            })
+            .collect();
+
+        let mut skills = HashMap::new();
+        for xp in &self.experiences {
+            collect_skills(xp, &mut skills);
+        }
+        let skills = self
+            .skill_categories()
+            .iter()
+            .filter_map(|category| {
+                skills.remove(category.name.as_str()).map(|keywords| Skill {
+                    name: category.display_name().into(),
+                    level: None,
+                    keywords,
+                })
+            })
+            .collect();
+        // only their display names are kept, as the names of the skills
+        if !self.skill_categories.is_empty() {
+            report.push("skill categories".into());
+        }
+        for name in self.tag_profiles.keys() {
+            report.push(format!("tag profiles.{name}"));
+        }
+
+        let languages = self
+            .languages
+            .iter()
+            .enumerate()
+            .map(|(idx, language)| {
+                if !language.comment.is_empty() {
+                    report.push(format!("languages[{idx}].comment"));
+                }
+                Language {
+                    language: language.language.clone(),
+                    fluency: non_empty(&language.level),
+                }
+            })
+            .collect();
+
+        let resume = JsonResume {
+            basics,
+            work,
+            education,
+            skills,
+            languages,
+        };
+        (resume, report)
+    }
+
+    /// Convert from JSON Resume
+    pub fn from_json_resume(resume: &JsonResume) -> (Self, ConversionReport) {
+        let mut report = ConversionReport::default();
+        let personal_data = PersonalData::from_json_resume(&resume.basics, &mut report);
+        let experiences = resume
+            .work
+            .iter()
+            .enumerate()
+            .map(|(idx, work)| CVEntry::from_json_resume_work(work, idx, &mut report))
+            .collect();
+        let education = resume
+            .education
+            .iter()
+            .enumerate()
+            .map(|(idx, edu)| CVEntry::from_json_resume_education(edu, idx, &mut report))
+            .collect();
+        // skills are attached to entries, not to the whole curriculum
+        for idx in 0..resume.skills.len() {
+            report.push(format!("skills[{idx}]"));
+        }
+        let languages = resume
+            .languages
+            .iter()
+            .map(|language| CVLanguage {
+                language: language.language.clone(),
+                level: language.fluency.clone().unwrap_or_default(),
+                comment: String::new(),
+            })
+            .collect();
+
+        let cv = Self {
+            personal_data,
+            education,
+            experiences,
+            languages,
+            ..Default::default()
+        };
+        (cv, report)
+    }
+}
+
+impl PersonalData {
+    fn to_json_resume(&self, report: &mut ConversionReport) -> Basics {
+        let mut profiles = Vec::new();
+        let socials = [
+            ("github", "https://github.com/", &self.github),
+            ("gitlab", "https://gitlab.com/", &self.gitlab),
+            ("linkedin", "https://www.linkedin.com/in/", &self.linkedin),
+            ("twitter", "https://twitter.com/", &self.twitter),
+        ];
+        for (network, base, account) in socials {
+            if let Some(account) = account {
+                profiles.push(Profile {
+                    network: network.into(),
+                    username: Some(account.clone()),
+                    url: Some(format!("{base}{account}")),
+                });
+            }
+        }
+        for (name, url) in &self.webpage {
+            profiles.push(Profile {
+                network: name.clone(),
+                username: None,
+                url: Some(url.clone()),
+            });
+        }
+        for idx in 1..self.mobile.len() {
+            report.push(format!("personal data.mobile[{idx}]"));
+        }
+        for (idx, email) in self.email.iter().enumerate() {
+            if idx > 0 {
+                report.push(format!("personal data.email[{idx}]"));
+            } else if email.name.is_some() {
+                report.push(format!("personal data.email[{idx}].name"));
+            }
+        }
+        Basics {
+            name: self.name.clone(),
+            label: self.title.clone(),
+            email: self.email.first().map(|e| e.mail.clone()),
+            phone: self.mobile.first().cloned(),
+            profiles,
+            ..Default::default()
+        }
+    }
+
+    fn from_json_resume(basics: &Basics, report: &mut ConversionReport) -> Self {
+        let mut data = Self {
+            name: basics.name.clone(),
+            title: basics.label.clone(),
+            mobile: basics.phone.iter().cloned().collect(),
+            email: basics
+                .email
+                .iter()
+                .map(|mail| CVEmail {
+                    name: None,
+                    mail: mail.clone(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        if let Some(url) = &basics.url {
+            data.webpage.push(("homepage".into(), url.clone()));
+        }
+        for profile in &basics.profiles {
+            let network = profile.network.to_lowercase();
+            let account = profile.username.clone().or_else(|| {
+                profile
+                    .url
+                    .as_ref()
+                    .and_then(|u| u.trim_end_matches('/').rsplit('/').next())
+                    .map(String::from)
+            });
+            match (network.as_str(), account) {
+                ("github", Some(a)) => data.github = Some(a),
+                ("gitlab", Some(a)) => data.gitlab = Some(a),
+                ("linkedin", Some(a)) => data.linkedin = Some(a),
+                ("twitter", Some(a)) => data.twitter = Some(a),
+                _ => match &profile.url {
+                    Some(url) if !NETWORKS.contains(&network.as_str()) => {
+                        data.webpage.push((profile.network.clone(), url.clone()))
+                    }
+                    _ => report.push(format!("basics.profiles.{}", profile.network)),
+                },
+            }
+        }
+        if basics.summary.is_some() {
+            report.push("basics.summary".into());
+        }
+        if basics.location.is_some() {
+            report.push("basics.location".into());
+        }
+        data
+    }
+}
+
+impl CVEntry {
+    /// report fields of this entry not handled by JSON Resume
+    fn report_unmapped(&self, path: &str, fields: &[(&str, bool)], report: &mut ConversionReport) {
+        for (field, is_set) in fields {
+            if *is_set {
+                report.push(format!("{path}.{field}"));
+            }
+        }
+        for idx in 0..self.subentries.len() {
+            report.push(format!("{path}.subentries[{idx}]"));
+        }
+    }
+
+    fn to_json_resume_work(&self, path: &str, report: &mut ConversionReport) -> Work {
+        let description = self.description.as_ref();
+        self.report_unmapped(
+            path,
+            &[
+                ("grade", self.grade.is_some()),
+                (
+                    "description.team",
+                    description.is_some_and(|d| !d.team.is_empty()),
+                ),
+                (
+                    "description.tasks",
+                    description.is_some_and(|d| !d.tasks.is_empty()),
+                ),
+                ("part time", self.part_time),
+                ("industry", self.industry.is_some()),
+                ("tags", !self.tags.is_empty()),
+                ("priority", self.priority != 0),
+            ],
+            report,
+        );
+        Work {
+            name: self.institution.to_string(),
+            position: non_empty(&self.degree.to_plain()),
+            location: self.city.as_ref().map(Text::to_string),
+            start_date: format_date(&self.beginning),
+            end_date: format_date(&self.end),
+            summary: description.and_then(|d| non_empty(&d.context.to_plain())),
+            highlights: description
+                .map(|d| d.achievements.iter().map(Text::to_string).collect())
+                .unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    fn to_json_resume_education(&self, path: &str, report: &mut ConversionReport) -> Education {
+        let description = self.description.as_ref();
+        self.report_unmapped(
+            path,
+            &[
+                ("city", self.city.is_some()),
+                ("description", description.is_some()),
+                ("part time", self.part_time),
+                ("industry", self.industry.is_some()),
+                ("tags", !self.tags.is_empty()),
+                ("priority", self.priority != 0),
+            ],
+            report,
+        );
+        Education {
+            institution: self.institution.to_string(),
+            study_type: non_empty(&self.degree.to_plain()),
+            start_date: format_date(&self.beginning),
+            end_date: format_date(&self.end),
+            score: self.grade.as_ref().map(Text::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn from_json_resume_work(work: &Work, idx: usize, report: &mut ConversionReport) -> Self {
+        if work.url.is_some() {
+            report.push(format!("work[{idx}].url"));
+        }
+        let description =
+            (work.summary.is_some() || !work.highlights.is_empty()).then(|| EntryDescription {
+                context: work.summary.clone().unwrap_or_default().into(),
+                achievements: work.highlights.iter().map(|h| h.as_str().into()).collect(),
+                ..Default::default()
+            });
+        Self {
+            beginning: parse_date(&work.start_date, format!("work[{idx}].startDate"), report),
+            end: parse_date(&work.end_date, format!("work[{idx}].endDate"), report),
+            degree: work.position.clone().unwrap_or_default().into(),
+            institution: work.name.as_str().into(),
+            city: work.location.clone().map(Text::from),
+            description,
+            ..Default::default()
+        }
+    }
+
+    fn from_json_resume_education(
+        edu: &Education,
+        idx: usize,
+        report: &mut ConversionReport,
+    ) -> Self {
+        if edu.url.is_some() {
+            report.push(format!("education[{idx}].url"));
+        }
+        if !edu.courses.is_empty() {
+            report.push(format!("education[{idx}].courses"));
+        }
+        let degree = [&edu.study_type, &edu.area]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            beginning: parse_date(
+                &edu.start_date,
+                format!("education[{idx}].startDate"),
+                report,
+            ),
+            end: parse_date(&edu.end_date, format!("education[{idx}].endDate"), report),
+            degree: degree.into(),
+            institution: edu.institution.as_str().into(),
+            grade: edu.score.clone().map(Text::from),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CVDuration;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn export() {
+        let data = r#"
+        {
+            "personal data": {
+                "name": "Jessica Meyer",
+                "title": "Environmental manager",
+                "mobile": ["+32 56 19 01", "+32 56 19 04"],
+                "email": [{"mail": "nom@example.com"}],
+                "github": "example",
+                "webpage": [["blog", "https://blog.example.org"]]
+            },
+            "skill categories": [{"name": "ci"}, {"name": "programming"}],
+            "tag profiles": {"short": {"disable": ["tasks"]}},
+            "education": [
+                {
+                    "beginning": "2010-09",
+                    "end": "2013-06",
+                    "degree": "MS",
+                    "institution": "Anderson LLC",
+                    "grade": "cum laude"
+                }
+            ],
+            "experiences": [
+                {
+                    "beginning": "2022-10",
+                    "end": "2023-11",
+                    "degree": "developer",
+                    "institution": "Brewer LLC",
+                    "part time": true,
+                    "industry": "energy",
+                    "description": {
+                        "context": "some super context",
+                        "achievements": ["shipped"],
+                        "tasks": ["coding"],
+                        "ci": ["git", "gitlab"]
+                    },
+                    "subentries": [
+                        {
+                            "description": {
+                                "ci": ["gitlab", "jenkins"],
+                                "programming": ["rust"]
+                            }
+                        }
+                    ]
+                }
+            ],
+            "languages": [{"language": "french", "level": "native", "comment": "mother tongue"}]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let (resume, report) = cv.to_json_resume();
+
+        assert_eq!(resume.basics.phone.as_deref(), Some("+32 56 19 01"));
+        assert_eq!(resume.basics.profiles.len(), 2);
+        assert_eq!(resume.work[0].start_date.as_deref(), Some("2022-10"));
+        assert_eq!(resume.work[0].position.as_deref(), Some("developer"));
+        assert_eq!(resume.work[0].highlights, vec!["shipped"]);
+        assert_eq!(resume.education[0].study_type.as_deref(), Some("MS"));
+        assert_eq!(resume.education[0].score.as_deref(), Some("cum laude"));
+        assert_eq!(resume.skills[0].name, "CI/CD");
+        assert_eq!(resume.skills[0].keywords, vec!["git", "gitlab", "jenkins"]);
+        assert_eq!(resume.skills[1].name, "programming languages");
+        assert_eq!(resume.languages[0].fluency.as_deref(), Some("native"));
+        assert_eq!(
+            report.unmapped,
+            vec![
+                "personal data.mobile[1]",
+                "experiences[0].description.tasks",
+                "experiences[0].part time",
+                "experiences[0].industry",
+                "experiences[0].subentries[0]",
+                "skill categories",
+                "tag profiles.short",
+                "languages[0].comment",
+            ]
+        );
+    }
+
+    #[test]
+    fn import() {
+        let data = r#"
+        {
+            "basics": {
+                "name": "John Doe",
+                "label": "Programmer",
+                "email": "john@gmail.com",
+                "phone": "(912) 555-4321",
+                "url": "https://johndoe.com",
+                "summary": "A summary of John Doe",
+                "profiles": [
+                    {"network": "Twitter", "username": "john", "url": "https://twitter.com/john"},
+                    {"network": "Mastodon", "url": "https://mastodon.social/@john"}
+                ]
+            },
+            "work": [{
+                "name": "Company",
+                "position": "President",
+                "url": "https://company.com",
+                "startDate": "2013-01-01",
+                "endDate": "2014-01",
+                "summary": "Description",
+                "highlights": ["Started the company"]
+            }],
+            "education": [{
+                "institution": "University",
+                "area": "Software Development",
+                "studyType": "Bachelor",
+                "startDate": "2011",
+                "endDate": "not a date",
+                "score": "4.0",
+                "courses": ["DB1101 - Basic SQL"]
+            }],
+            "skills": [{"name": "Web Development", "keywords": ["HTML", "CSS"]}],
+            "languages": [{"language": "English", "fluency": "Native speaker"}]
+        }
+        "#;
+        let resume: JsonResume = serde_json::from_str(data).unwrap();
+        let (cv, report) = Curriculum::from_json_resume(&resume);
+
+        assert_eq!(cv.personal_data.twitter.as_deref(), Some("john"));
+        assert_eq!(cv.personal_data.webpage.len(), 2);
+        assert_eq!(cv.experiences[0].degree, "President");
+        assert_eq!(
+            cv.experiences[0].description.as_ref().unwrap().achievements,
+            vec!["Started the company"]
+        );
+        let today = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let (beginning, end) = cv.experiences[0].span(today).unwrap();
+        assert_eq!(CVDuration::from(end - beginning).year, 1);
+        assert_eq!(cv.education[0].degree, "Bachelor Software Development");
+        assert!(cv.education[0].beginning.is_some());
+        assert!(cv.education[0].end.is_none());
+        assert_eq!(cv.languages[0].level, "Native speaker");
+        assert_eq!(
+            report.unmapped,
+            vec![
+                "basics.summary",
+                "work[0].url",
+                "education[0].courses",
+                "education[0].endDate",
+                "skills[0]",
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica", "linkedin": "jessica"},
+            "education": [],
+            "experiences": [
+                {
+                    "beginning": "2022-10",
+                    "institution": "Brewer LLC",
+                    "description": {"achievements": ["shipped"]}
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let (resume, report) = cv.to_json_resume();
+        assert!(report.is_empty());
+        let (back, report) = Curriculum::from_json_resume(&resume);
+        assert!(report.is_empty());
+        assert_eq!(
+            serde_json::to_value(&cv).unwrap(),
+            serde_json::to_value(&back).unwrap()
+        );
+    }
+}
@@ -1,78 +1,260 @@
 use anyhow::Result;
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::ops::Add;
-use unidecode::unidecode;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::ops::{Add, Not};
+
+mod aliases;
+mod categorize;
+mod chart;
+mod filter;
+mod html;
+pub mod json_resume;
+mod markdown;
+mod profile;
+mod report;
+mod source;
+mod stats;
+mod strict;
+mod tags;
+mod timeline;
+mod validate;
+
+pub use aliases::{MergeReport, SkillAliases};
+pub use categorize::{CategorySuggestion, TechnologyDictionary};
+pub use cv_date::{CVDate, Precision};
+pub use filter::EntryFilter;
+pub use profile::TagProfile;
+pub use report::{SkillReport, SkillRow, SkillSort};
+pub use source::SourceFormat;
+pub use stats::CurriculumStats;
+pub use strict::{UnknownField, UnknownFields};
+use tags::normalize_tag;
+pub use tags::TagNames;
+pub use timeline::TimelineOptions;
+pub use validate::{IssueKind, ValidationIssue, ValidationOptions};
 
 static PREAMBULE: &str = include_str!("../data/preambule.tex");
 
-/// list of ordered skill categories
-const SKILL_CATEGORIES: &[&str] = &[
-    "programming languages",
-    "version control",
-    "database",
-    "cloud computing",
-    "CI/CD",
-    "other",
+/// number of skills per category on the first page
+const FIRST_PAGE_SKILLS: usize = 3;
+/// number of roles on the first page
+const FIRST_PAGE_ROLES: usize = 3;
+
+/// default skill categories, in display order
+/// (key, display name)
+const SKILL_CATEGORIES: &[(&str, &str)] = &[
+    ("programming", "programming languages"),
+    ("version", "version control"),
+    ("database", "database"),
+    ("cloud", "cloud computing"),
+    ("ci", "CI/CD"),
+    ("other", "other"),
 ];
 
-#[derive(Debug)]
+/// industry of an entry, any other industry being written as is
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 enum Industry {
     Energy,
     Telecommunications,
     Health,
     Insurance,
     Automotive,
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl fmt::Display for Industry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Energy => write!(f, "energy"),
+            Self::Telecommunications => write!(f, "telecommunications"),
+            Self::Health => write!(f, "health"),
+            Self::Insurance => write!(f, "insurance"),
+            Self::Automotive => write!(f, "automotive"),
+            Self::Other(industry) => write!(f, "{industry}"),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
 struct CVEntry {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[serde(with = "cv_date")]
-    beginning: Option<DateTime<Utc>>,
-    #[serde(default)]
+    beginning: Option<CVDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[serde(with = "cv_date")]
-    end: Option<DateTime<Utc>>,
+    end: Option<CVDate>,
     /// degree or title or name
-    #[serde(default)]
-    degree: String,
+    #[serde(default, skip_serializing_if = "Text::is_empty")]
+    degree: Text,
     /// insititution or company
-    #[serde(default)]
-    institution: String,
-    #[serde(default)]
-    city: Option<String>,
-    #[serde(default)]
-    grade: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Text::is_empty")]
+    institution: Text,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    city: Option<Text>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    grade: Option<Text>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     description: Option<EntryDescription>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     subentries: Vec<CVEntry>,
+    /// part-time entries may overlap other entries
+    #[serde(default, rename = "part time", skip_serializing_if = "Not::not")]
+    part_time: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    industry: Option<Industry>,
+    /// user-defined tags, e.g. `backend` or `consulting`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// entries of higher priority come first once sorted
+    #[serde(default, skip_serializing_if = "is_zero")]
+    priority: i32,
 }
 
-/// transform tag in LaTeX-safe string
-fn normalize_tag(tag: &str) -> String {
-    unidecode(tag)
-        .replace(&[' ', ',', '-', '&', '/', '\\', '\''][..], "")
-        .into()
+fn is_zero(n: &i32) -> bool {
+    *n == 0
 }
 
 /// transform text with multiple paragraph in LaTeX
-fn format_long_text(text: &str) -> String {
-    text.replace('\n', "\\\\%\n").into()
+fn format_long_text(text: &Text) -> String {
+    text.to_latex().replace('\n', "\\\\%\n")
+}
+
+/// escape LaTeX special characters
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// plain text of LaTeX, commands and braces being removed
+fn strip_latex(latex: &str) -> String {
+    let mut text = String::with_capacity(latex.len());
+    let mut chars = latex.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\\') => text.push('\n'),
+                Some(c) if c.is_ascii_alphabetic() => {
+                    // command name and the spaces ending it
+                    while chars.next_if(char::is_ascii_alphabetic).is_some() {}
+                    while chars.next_if(|c| *c == ' ').is_some() {}
+                }
+                Some(c) => text.push(c),
+                None => (),
+            },
+            '{' | '}' | '$' => (),
+            '~' => text.push(' '),
+            '%' => while chars.next_if(|c| *c != '\n').is_some() {},
+            c => text.push(c),
+        }
+    }
+    text.trim().into()
+}
+
+/// escape the characters of a URL that hyperref cannot read in an argument
+fn escape_url(url: &str) -> String {
+    url.replace('%', "\\%").replace('#', "\\#")
+}
+
+/// Text provided by the user
+///
+/// Plain text is escaped in the LaTeX output. Text intentionally
+/// containing LaTeX is written `{"latex": "..."}` and inserted as is; other
+/// outputs use its [plain text](Text::to_plain).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+enum Text {
+    Plain(String),
+    Raw { latex: String },
+}
+
+impl Text {
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Plain(text) => text.is_empty(),
+            Self::Raw { latex } => latex.is_empty(),
+        }
+    }
+
+    /// text without LaTeX, for the outputs other than LaTeX
+    fn to_plain(&self) -> String {
+        match self {
+            Self::Plain(text) => text.clone(),
+            Self::Raw { latex } => strip_latex(latex),
+        }
+    }
+
+    fn to_latex(&self) -> String {
+        match self {
+            Self::Plain(text) => escape_latex(text),
+            Self::Raw { latex } => latex.clone(),
+        }
+    }
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Self::Plain(String::new())
+    }
+}
+
+impl fmt::Display for Text {
+    /// plain text
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_plain())
+    }
+}
+
+impl PartialEq<&str> for Text {
+    fn eq(&self, other: &&str) -> bool {
+        self.to_plain() == *other
+    }
+}
+
+impl From<String> for Text {
+    fn from(text: String) -> Self {
+        Self::Plain(text)
+    }
+}
+
+impl From<&str> for Text {
+    fn from(text: &str) -> Self {
+        Self::Plain(text.into())
+    }
 }
 
 impl CVEntry {
     /// Produce corresponding LaTeX
-    fn to_latex(&self, width: Option<f32>, tags: &mut HashSet<String>) -> String {
+    fn to_latex(
+        &self,
+        width: Option<f32>,
+        categories: &[SkillCategory],
+        names: &TagNames,
+        tags: &mut BTreeSet<String>,
+    ) -> String {
         let mut descr = match &self.description {
             Some(d) => {
-                let tag = normalize_tag(&self.institution.clone());
+                let tag = names.tag(&self.institution.to_string());
                 tags.insert(tag.clone());
                 format!(
                     "\\if{tag}% beginning of {tag}\n{}\n\\fi% end of {tag}",
-                    d.to_latex(tags)
+                    d.to_latex(categories, tags)
                 )
             }
             None => "".into(),
@@ -88,29 +270,35 @@ impl CVEntry {
         for subentry in &self.subentries {
             descr.push_str("%\n");
             let margin = max_date_len.as_ref().map(|d| 21.5 - *d as f32);
-            if margin.is_some() {
-                descr.push_str(&format!("\\hspace*{{-{}ex}}", margin.unwrap()));
-            }
-            descr.push_str(&subentry.to_latex(margin, tags));
+            descr.push_str(&subentry.to_latex(margin, categories, names, tags));
         }
-        format!(
-            "\\cventry{{{}}}{{{}}}{{{}}}{{{}}}{{{}}}{{\n{}%\n}}",
+        let indent = match width {
+            Some(width) => format!("\\hspace*{{-{}ex}}", width),
+            None => "".into(),
+        };
+        let entry = format!(
+            "{indent}\\cventry{{{}}}{{{}}}{{{}}}{{{}}}{{{}}}{{\n{}%\n}}",
             &self.get_dates(),
-            &self.degree, // title
-            &self.institution,
-            &self.city.clone().unwrap_or("".into()),
-            &self.grade.clone().unwrap_or("".into()),
+            &self.degree.to_latex(), // title
+            &self.institution.to_latex(),
+            &self.city.as_ref().map(Text::to_latex).unwrap_or_default(),
+            &self.grade.as_ref().map(Text::to_latex).unwrap_or_default(),
             descr.trim(),
-        )
+        );
+        self.tags.iter().fold(entry, |entry, tag| {
+            let tag = names.tag(tag);
+            tags.insert(tag.clone());
+            format!("\\if{tag}% beginning of {tag}\n{entry}\n\\fi% end of {tag}")
+        })
     }
 
     fn get_dates(&self) -> String {
         let mut dates: Vec<String> = Vec::new();
         if let Some(b) = self.beginning {
-            dates.push(format!("{}", b.format("%Y")))
+            dates.push(b.year())
         };
         if let Some(e) = self.end {
-            dates.push(format!("{}", e.format("%Y")))
+            dates.push(e.year())
         };
         dates.join("--")
     }
@@ -125,123 +313,134 @@ impl CVEntry {
         }
     }
 
-    /// get skills with duration
-    /// {category: {skill: duration}}
-    fn extract_skills_duration(&self) -> HashMap<&str, HashMap<String, Duration>> {
-        if let Some(desc) = &self.description {
-            (desc.extract_skills().iter().map(|(&category, skills)| {
-                (
-                    category,
-                    skills
-                        .iter()
-                        .map(|s| (s.clone(), self.duration().unwrap_or(Duration::zero())))
-                        .collect::<HashMap<_, _>>(),
-                )
-            }))
-            .collect::<HashMap<_, _>>()
-        } else {
-            HashMap::new()
+    /// apply f to the lists of skills of the entry and its subentries
+    fn for_each_skill_list(&mut self, f: &mut impl FnMut(&mut Vec<String>)) {
+        if let Some(desc) = &mut self.description {
+            desc.skill_lists_mut().for_each(&mut *f);
+        }
+        for subentry in &mut self.subentries {
+            subentry.for_each_skill_list(f);
         }
     }
 
-    /// get skills with duration, including subentries
-    /// {category: {skill: duration}}
-    fn extract_subentries_skills(&self) -> HashMap<&str, HashMap<String, Duration>> {
-        let skills = self.extract_skills_duration();
-        self.subentries.iter().fold(skills, |mut acc, entry| {
-            add_skillsets(&mut acc, entry.extract_subentries_skills());
-            acc
-        })
-    }
-
-    /// return duration of this entry
-    fn duration(&self) -> Option<Duration> {
-        if let Some(b) = &self.beginning {
-            if let Some(e) = &self.end {
-                Some(*e - b)
-            } else {
-                Some(Utc::now() - b)
+    /// get dates during which skills were used, including subentries
+    /// {category: {skill: [(beginning, end)]}}
+    fn extract_skill_intervals<'a>(
+        &'a self,
+        today: DateTime<Utc>,
+        parent: Option<Interval>,
+        acc: &mut HashMap<&'a str, HashMap<String, Vec<Interval>>>,
+    ) {
+        let span = self.span_within(today, parent);
+        for (category, skills) in self.extract_skills() {
+            let cat = acc.entry(category).or_default();
+            for skill in skills {
+                cat.entry(skill)
+                    .or_default()
+                    .extend(span.filter(|(b, e)| b <= e));
             }
-        } else {
-            None
+        }
+        for subentry in &self.subentries {
+            subentry.extract_skill_intervals(today, span, acc);
         }
     }
 
-    fn cv_duration(&self) -> Option<CVDuration> {
-        if let Some(duration) = &self.duration() {
-            let duration: u32 = duration.num_days() as u32;
-            let year = duration / 365;
-            let remaining_days = duration % 365;
-            let month = (remaining_days + 15) / 30;
-            Some(CVDuration { year, month })
-        } else {
-            None
-        }
+    /// dates of the entry, ongoing entries ending today
+    fn span(&self, today: DateTime<Utc>) -> Option<Interval> {
+        self.span_within(today, None)
     }
-}
 
-/// Add skillset from other to acc
-fn add_skillsets<'a, I, S>(
-    acc: &mut HashMap<&'a str, HashMap<String, Duration>>,
-    other: HashMap<&'a str, I>,
-) where
-    I: IntoIterator<Item = (S, Duration)>,
-    S: ToString,
-    String: From<S>,
-{
-    for (category, skills) in other {
-        let cat = acc.entry(category).or_default();
-        for (skill, duration) in skills {
-            cat.entry(skill.into())
-                .and_modify(|d| *d += duration)
-                .or_insert(duration);
-        }
+    /// dates of the entry, missing dates being the ones of the parent entry
+    fn span_within(&self, today: DateTime<Utc>, parent: Option<Interval>) -> Option<Interval> {
+        let beginning = match self.beginning {
+            Some(b) => b.date()?,
+            None => parent?.0,
+        };
+        let end = match self.end {
+            Some(e) => e.date().unwrap_or(today),
+            None => parent.map_or(today, |(_, end)| end),
+        };
+        Some((beginning, end))
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
 struct EntryDescription {
-    #[serde(default)]
-    context: String,
-    #[serde(default)]
-    achievements: Vec<String>,
-    #[serde(default)]
-    team: String,
+    #[serde(default, skip_serializing_if = "Text::is_empty")]
+    context: Text,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    achievements: Vec<Text>,
+    #[serde(default, skip_serializing_if = "Text::is_empty")]
+    team: Text,
     /// task and responsabilities
-    #[serde(default)]
-    tasks: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tasks: Vec<Text>,
     /// technologies
     /// programming language
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     programming: Vec<String>,
     /// version control
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     version: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     database: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     cloud: Vec<String>,
     /// CI/CD
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     ci: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     other: Vec<String>,
+    /// skills of any category
+    /// {category: [skills]}
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    skills: BTreeMap<String, Vec<String>>,
 }
 
 impl EntryDescription {
+    /// get skills, legacy fields being merged with the skills of the same
+    /// category
+    /// {category: [skills]}
     fn extract_skills(&self) -> HashMap<&str, Vec<String>> {
-        let mut skills = HashMap::new();
-        skills.insert("programming languages", self.programming.clone());
-        skills.insert("version control", self.version.clone());
-        skills.insert("database", self.database.clone());
-        skills.insert("cloud computing", self.cloud.clone());
-        skills.insert("CI/CD", self.ci.clone());
-        skills.insert("other", self.other.clone());
+        let legacy = [
+            ("programming", &self.programming),
+            ("version", &self.version),
+            ("database", &self.database),
+            ("cloud", &self.cloud),
+            ("ci", &self.ci),
+            ("other", &self.other),
+        ];
+        let mut skills: HashMap<&str, Vec<String>> = HashMap::new();
+        let all = legacy
+            .into_iter()
+            .chain(self.skills.iter().map(|(c, s)| (c.as_str(), s)));
+        for (category, list) in all {
+            let known = skills.entry(category).or_default();
+            for skill in list {
+                if !known.contains(skill) {
+                    known.push(skill.clone());
+                }
+            }
+        }
         skills.retain(|_, v| !&v.is_empty());
         skills
     }
 
-    fn to_latex(&self, tags: &mut HashSet<String>) -> String {
+    /// lists of skills, legacy fields included
+    fn skill_lists_mut(&mut self) -> impl Iterator<Item = &mut Vec<String>> {
+        [
+            &mut self.programming,
+            &mut self.version,
+            &mut self.database,
+            &mut self.cloud,
+            &mut self.ci,
+            &mut self.other,
+        ]
+        .into_iter()
+        .chain(self.skills.values_mut())
+    }
+
+    fn to_latex(&self, categories: &[SkillCategory], tags: &mut BTreeSet<String>) -> String {
         let mut lines: Vec<String> = Vec::new();
         lines.push("%".into());
         if !self.context.is_empty() {
@@ -257,7 +456,11 @@ impl EntryDescription {
         }
         if !self.team.is_empty() {
             lines.push("% ---- begin team".into());
-            lines.push(get_titled_description("Team", &self.team, tags));
+            lines.push(get_titled_description(
+                "Team",
+                &format_long_text(&self.team),
+                tags,
+            ));
             lines.push("% ---- end   team".into());
         }
         if !&self.tasks.is_empty() {
@@ -270,9 +473,14 @@ impl EntryDescription {
             lines.push("% ---- begin skills".into());
             let mut techno = Vec::new();
             techno.push("\\begin{description}".into());
-            for name in SKILL_CATEGORIES {
-                if let Some(list) = skills.get(name) {
-                    techno.push(format!("\\item [{}] {}", name, list.join(", ")))
+            for category in categories {
+                if let Some(list) = skills.get(category.name.as_str()) {
+                    let list = list.iter().map(|s| escape_latex(s)).collect::<Vec<_>>();
+                    techno.push(format!(
+                        "\\item [{}] {}",
+                        escape_latex(category.display_name()),
+                        list.join(", ")
+                    ))
                 }
             }
             techno.push("\\end{description}".into());
@@ -293,45 +501,174 @@ pub struct Curriculum {
     personal_data: PersonalData,
     education: Vec<CVEntry>,
     experiences: Vec<CVEntry>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     languages: Vec<CVLanguage>,
+    /// Categories of skills, in display order
+    #[serde(
+        default,
+        rename = "skill categories",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    skill_categories: Vec<SkillCategory>,
+    /// named profiles of conditional tags
+    #[serde(
+        default,
+        rename = "tag profiles",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    tag_profiles: BTreeMap<String, TagProfile>,
+}
+
+/// Category of skills
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SkillCategory {
+    /// key of the category in entry descriptions
+    pub name: String,
+    /// name of the category in documents, the key if not given
+    #[serde(
+        default,
+        rename = "display name",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub display_name: Option<String>,
+}
+
+impl SkillCategory {
+    /// name of the category in documents
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// default categories, corresponding to the fields of descriptions
+    fn defaults() -> Vec<Self> {
+        SKILL_CATEGORIES
+            .iter()
+            .map(|(name, display_name)| Self {
+                name: name.to_string(),
+                display_name: Some(display_name.to_string()),
+            })
+            .collect()
+    }
+}
+
+/// Date of the "until now" computations
+///
+/// `SOURCE_DATE_EPOCH`, in seconds since 1970, if set, so that outputs are
+/// reproducible, now otherwise.
+pub fn reference_date() -> DateTime<Utc> {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse().ok())
+        .and_then(|epoch| DateTime::from_timestamp(epoch, 0))
+        .unwrap_or_else(Utc::now)
+}
+
+/// Options of the LaTeX generation
+#[derive(Debug, Clone)]
+pub struct LatexOptions {
+    /// skills used for a shorter duration are not listed in the skills section
+    pub min_skill_duration: CVDuration,
+    /// maximum number of skills listed per category in the skills section
+    pub max_skills_per_category: Option<usize>,
+    /// skills of education entries are counted as experience
+    pub education_skills: bool,
+    /// conditional tags enabled
+    pub tags: TagProfile,
+    /// number of skills of the skill chart
+    pub chart_skills: usize,
+    /// date of the document, see [`reference_date`]
+    pub today: DateTime<Utc>,
+}
+
+impl Default for LatexOptions {
+    fn default() -> Self {
+        Self {
+            min_skill_duration: CVDuration::default(),
+            max_skills_per_category: None,
+            education_skills: false,
+            tags: TagProfile::default(),
+            chart_skills: 10,
+            today: reference_date(),
+        }
+    }
+}
+
+impl LatexOptions {
+    /// options of the skill aggregation, at the date of the document
+    fn skill_options(&self) -> SkillOptions {
+        SkillOptions {
+            include_education: self.education_skills,
+            today: self.today,
+        }
+    }
+}
+
+/// skills of a category, most used first
+fn sorted_skills(skills: &HashMap<String, SkillExperience>) -> Vec<(&String, &SkillExperience)> {
+    let mut skills = skills.iter().collect::<Vec<_>>();
+    skills.sort_by(|(s1, d1), (s2, d2)| d2.cmp(d1).then(s1.cmp(s2)));
+    skills
 }
 
-/// create latex corresponding to conditional tag compilation
-fn conditional_tags(tags: HashSet<String>) -> String {
-    tags.iter()
-        .map(|tag| format!("\\newif\\if{tag}%\n%\\{tag}false\n\\{tag}true\n"))
+/// LaTeX list of skills with their rounded calendar duration
+fn format_skills(skills: &[(&String, &SkillExperience)]) -> String {
+    skills
+        .iter()
+        .map(|(skill, xp)| format!("{} ({})", escape_latex(skill), xp.calendar.round()))
         .collect::<Vec<_>>()
-        .join("\n")
+        .join(", ")
+}
+
+/// packages of the blocks emitted and enabled by the profile
+fn latex_packages(options: &LatexOptions, names: &TagNames, tags: &BTreeSet<String>) -> String {
+    let enabled = |tag: &str| tags.contains(tag) && options.tags.is_enabled_with(tag, names);
+    let mut lines: Vec<String> = Vec::new();
+    if enabled("timeline") {
+        lines.push("\\usepackage{tikz}\t% timeline".into());
+    }
+    if enabled("skillchart") {
+        lines.push("\\usepackage{pgfplots}\t% skill chart".into());
+        lines.push("\\pgfplotsset{compat=1.16}".into());
+    }
+    lines.join("\n")
 }
 
 impl Curriculum {
     /// Generate the LaTeX corresponding to the whole document
     pub fn to_latex(&self) -> Result<String> {
+        self.to_latex_with(&LatexOptions::default())
+    }
+
+    /// Generate the LaTeX corresponding to the whole document, with options
+    pub fn to_latex_with(&self, options: &LatexOptions) -> Result<String> {
         let mut output = Vec::new();
-        let mut tags = HashSet::new(); // conditional compilation tags
+        let mut tags = BTreeSet::new(); // conditional compilation tags
         let preamb = PREAMBULE.into();
-
-        // TODO replace with first page
-        // TODO add skills
+        let categories = self.skill_categories();
+        let names = self.tag_names();
 
         // sections
         output.push(self.personal_data.to_latex());
         output.push("\n\\begin{document}\n".into());
         output.push("\\maketitle".into());
+        output.push(self.timeline_to_latex(options, &mut tags));
+        output.push(self.make_first_page(options, &mut tags));
 
         output.push("\\section{Education}".into());
         for edu in &self.education {
-            output.push(edu.to_latex(None, &mut tags));
+            output.push(edu.to_latex(None, &categories, &names, &mut tags));
             output.push("\n".into());
         }
 
         output.push("\\section{Proffesional experience}".into());
         for experience in &self.experiences {
-            output.push(experience.to_latex(None, &mut tags));
+            output.push(experience.to_latex(None, &categories, &names, &mut tags));
             output.push("\n".into());
         }
 
+        output.push(self.skills_to_latex(options, &mut tags));
+        output.push(self.skill_chart_to_latex(options, &mut tags));
+
         output.push("\\section{Languages}".into());
         for language in &self.languages {
             output.push(language.to_latex());
@@ -340,7 +677,9 @@ impl Curriculum {
         output.push("\\end{document}".into());
 
         // preambule
-        output.insert(0, conditional_tags(tags));
+        let packages = latex_packages(options, &names, &tags);
+        output.insert(0, profile::conditional_tags(tags, &options.tags, &names));
+        output.insert(0, packages);
         output.insert(0, String::from_utf8(preamb)?);
 
         Ok(output.join("\n"))
@@ -351,7 +690,13 @@ impl Curriculum {
     /// if path is not None, write file
     /// return the content of the pdf file
     pub fn to_pdf(&self, path: Option<&Path>) -> Result<Vec<u8>> {
-        let tex_data = &self.to_latex()?;
+        self.to_pdf_with(path, &LatexOptions::default())
+    }
+
+    #[cfg(feature = "pdf")]
+    /// Generate pdf, with options
+    pub fn to_pdf_with(&self, path: Option<&Path>, options: &LatexOptions) -> Result<Vec<u8>> {
+        let tex_data = &self.to_latex_with(options)?;
         if let Some(tex_path) = path {
             let tex_path = tex_path.with_extension("tex");
             println!(
@@ -372,37 +717,276 @@ impl Curriculum {
         Ok(pdf_data)
     }
 
-    /// Get skills from entries
-    /// {category: {skill: duration}}
-    fn get_skills(&self) -> HashMap<&str, HashMap<String, CVDuration>> {
-        let mut ret_skills = HashMap::new();
-        for xp in &self.experiences {
-            let duration = &xp.cv_duration().unwrap_or_default();
-            let entry_skills = xp.extract_skills();
-            for (categ, ref skills) in entry_skills {
-                let ret_categ: &mut HashMap<String, _> = ret_skills.entry(categ).or_default();
-                for skill in skills {
-                    let s: &mut CVDuration = ret_categ.entry(skill.clone()).or_default();
-                    *s = s.clone() + duration.clone();
+    /// create the first page
+    ///
+    /// The first page sums up the resume, including
+    /// * the total experience
+    /// * technical knowledge, the most used skills of each category
+    /// * industry knowledge (in which industry your work in)
+    /// * the most recent roles
+    fn make_first_page(&self, options: &LatexOptions, tags: &mut BTreeSet<String>) -> String {
+        tags.insert("firstpage".into());
+        let mut lines: Vec<String> = Vec::new();
+        lines.push("% ---- begin first page".into());
+        lines.push("\\iffirstpage%".into());
+        lines.push("\\section{Summary}".into());
+
+        let today = options.today;
+        let intervals = self
+            .experiences
+            .iter()
+            .filter_map(|xp| xp.span(today))
+            .collect();
+        let total = CVDuration::from(covered_duration(intervals)).round();
+        lines.push(format!("\\cvitem{{experience}}{{{total}}}"));
+
+        let industries = self.industries();
+        if !industries.is_empty() {
+            let industries = industries
+                .iter()
+                .map(|i| escape_latex(&i.to_string()))
+                .collect::<Vec<_>>();
+            lines.push(format!(
+                "\\cvitem{{industries}}{{{}}}",
+                industries.join(", ")
+            ));
+        }
+
+        let skills = self.get_skills_with(&options.skill_options());
+        if !skills.is_empty() {
+            lines.push("\\subsection{Technical skills}".into());
+            for category in self.skill_categories() {
+                if let Some(list) = skills.get(category.name.as_str()) {
+                    let mut top = sorted_skills(list);
+                    top.truncate(FIRST_PAGE_SKILLS);
+                    lines.push(format!(
+                        "\\cvitem{{{}}}{{{}}}",
+                        escape_latex(category.display_name()),
+                        format_skills(&top)
+                    ));
+                }
+            }
+        }
+
+        let mut roles = self.experiences.iter().collect::<Vec<_>>();
+        roles.sort_by_key(|xp| Reverse(xp.beginning.map(|b| b.date().unwrap_or(today))));
+        if !roles.is_empty() {
+            lines.push("\\subsection{Recent roles}".into());
+            for xp in roles.iter().take(FIRST_PAGE_ROLES) {
+                let role = [&xp.degree, &xp.institution]
+                    .iter()
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_latex())
+                    .collect::<Vec<_>>();
+                lines.push(format!(
+                    "\\cvitem{{{}}}{{{}}}",
+                    xp.get_dates(),
+                    role.join(", ")
+                ));
+            }
+        }
+        lines.push("\\clearpage".into());
+        lines.push("\\fi% ---- end   first page".into());
+        lines.join("\n")
+    }
+
+    /// create the skills section
+    /// skills of each category are sorted by calendar experience
+    fn skills_to_latex(&self, options: &LatexOptions, tags: &mut BTreeSet<String>) -> String {
+        tags.insert("skills".into());
+        let skills = self.get_skills_with(&options.skill_options());
+        let mut lines: Vec<String> = Vec::new();
+        lines.push("% ---- begin skills section".into());
+        lines.push("\\ifskills%".into());
+        lines.push("\\section{Skills}".into());
+        for category in self.skill_categories() {
+            let Some(list) = skills.get(category.name.as_str()) else {
+                continue;
+            };
+            let mut list = sorted_skills(list);
+            list.retain(|(_, xp)| xp.calendar >= options.min_skill_duration);
+            if let Some(max) = options.max_skills_per_category {
+                list.truncate(max);
+            }
+            if !list.is_empty() {
+                lines.push(format!(
+                    "\\cvitem{{{}}}{{{}}}",
+                    escape_latex(category.display_name()),
+                    format_skills(&list)
+                ));
+            }
+        }
+        lines.push("\\fi% ---- end   skills section".into());
+        lines.join("\n")
+    }
+
+    /// apply f to the lists of skills of all entries
+    fn for_each_skill_list(&mut self, f: &mut impl FnMut(&mut Vec<String>)) {
+        for entry in self.experiences.iter_mut().chain(&mut self.education) {
+            entry.for_each_skill_list(f);
+        }
+    }
+
+    /// Categories of skills, in display order
+    ///
+    /// The categories are the configured ones, or the default ones if none
+    /// is configured, followed by the other categories used in entries.
+    pub fn skill_categories(&self) -> Vec<SkillCategory> {
+        fn collect<'a>(entry: &'a CVEntry, used: &mut BTreeSet<&'a str>) {
+            used.extend(entry.extract_skills().into_keys());
+            for subentry in &entry.subentries {
+                collect(subentry, used);
+            }
+        }
+        let defaults = SkillCategory::defaults();
+        let mut categories = if self.skill_categories.is_empty() {
+            defaults.clone()
+        } else {
+            self.skill_categories
+                .iter()
+                .map(|category| {
+                    let default = defaults.iter().find(|d| d.name == category.name);
+                    SkillCategory {
+                        name: category.name.clone(),
+                        display_name: category
+                            .display_name
+                            .clone()
+                            .or_else(|| default.and_then(|d| d.display_name.clone())),
+                    }
+                })
+                .collect()
+        };
+        let mut used = BTreeSet::new();
+        for entry in self.experiences.iter().chain(&self.education) {
+            collect(entry, &mut used);
+        }
+        for name in used {
+            if !categories.iter().any(|c| c.name == name) {
+                let default = defaults.iter().find(|d| d.name == name);
+                categories.push(default.cloned().unwrap_or_else(|| SkillCategory {
+                    name: name.into(),
+                    display_name: None,
+                }));
+            }
+        }
+        categories
+    }
+
+    /// industries of experiences and their subentries, in order of appearance
+    fn industries(&self) -> Vec<&Industry> {
+        fn collect<'a>(entry: &'a CVEntry, industries: &mut Vec<&'a Industry>) {
+            if let Some(industry) = &entry.industry {
+                if !industries.contains(&industry) {
+                    industries.push(industry);
                 }
             }
+            for subentry in &entry.subentries {
+                collect(subentry, industries);
+            }
+        }
+        let mut industries = Vec::new();
+        for xp in &self.experiences {
+            collect(xp, &mut industries);
+        }
+        industries
+    }
+
+    /// Get skills from experiences and their subentries
+    /// {category: {skill: experience}}
+    pub fn get_skills(&self) -> HashMap<&str, HashMap<String, SkillExperience>> {
+        self.get_skills_with(&SkillOptions::default())
+    }
+
+    /// Get skills from entries and their subentries, with options
+    /// {category: {skill: experience}}
+    pub fn get_skills_with(
+        &self,
+        options: &SkillOptions,
+    ) -> HashMap<&str, HashMap<String, SkillExperience>> {
+        let today = options.today;
+        let mut intervals = HashMap::new();
+        let education = if options.include_education {
+            &self.education[..]
+        } else {
+            &[]
+        };
+        for entry in self.experiences.iter().chain(education) {
+            entry.extract_skill_intervals(today, None, &mut intervals);
+        }
+        intervals
+            .into_iter()
+            .map(|(categ, skills)| {
+                let skills = skills
+                    .into_iter()
+                    .map(|(skill, intervals)| (skill, SkillExperience::from(intervals)))
+                    .collect();
+                (categ, skills)
+            })
+            .collect()
+    }
+}
+
+/// Options of the skill aggregation
+#[derive(Debug, Clone)]
+pub struct SkillOptions {
+    /// skills of education entries are counted as experience
+    pub include_education: bool,
+    /// end of the ongoing entries
+    pub today: DateTime<Utc>,
+}
+
+impl Default for SkillOptions {
+    fn default() -> Self {
+        Self {
+            include_education: false,
+            today: reference_date(),
+        }
+    }
+}
+
+/// Experience in a skill
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct SkillExperience {
+    /// calendar time using the skill, concurrent entries being counted once
+    pub calendar: CVDuration,
+    /// sum of the durations of the entries using the skill
+    pub cumulative: CVDuration,
+    /// year the last entry using the skill ended
+    pub last_used: Option<i32>,
+}
+
+impl From<Vec<Interval>> for SkillExperience {
+    fn from(intervals: Vec<Interval>) -> Self {
+        let cumulative = intervals
+            .iter()
+            .fold(Duration::zero(), |acc, (beginning, end)| {
+                acc + (*end - *beginning)
+            });
+        let last_used = intervals.iter().map(|(_, end)| end.year()).max();
+        Self {
+            calendar: covered_duration(intervals).into(),
+            cumulative: cumulative.into(),
+            last_used,
         }
-        ret_skills
     }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct CVEmail {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     name: Option<String>,
     mail: String,
 }
 
 impl CVEmail {
     fn to_latex(&self) -> String {
-        let link = format!("\\href{{mailto:{}}}{{{}}}", self.mail, self.mail);
+        let link = format!(
+            "\\href{{mailto:{}}}{{{}}}",
+            escape_url(&self.mail),
+            escape_latex(&self.mail)
+        );
         if let Some(name) = &self.name {
-            format!("{}: {}", name, link)
+            format!("{}: {}", escape_latex(name), link)
         } else {
             link
         }
@@ -412,17 +996,22 @@ impl CVEmail {
 #[derive(Serialize, Deserialize, Debug, Default)]
 struct PersonalData {
     name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     title: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     mobile: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     email: Vec<CVEmail>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     github: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     gitlab: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     twitter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     linkedin: Option<String>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     /// [(name, url), ]
     webpage: Vec<(String, String)>,
 }
@@ -432,36 +1021,43 @@ impl PersonalData {
         let mut lines = Vec::new();
         lines.push("% personal data".into());
         let names = &self.name.split(' ').collect::<Vec<_>>();
-        let first_name = names[0];
+        let first_name = escape_latex(names[0]);
         lines.push(format!("\\firstname{{\\LARGE {first_name}}}"));
         if let Some(last_name) = names.get(1) {
+            let last_name = escape_latex(last_name);
             lines.push(format!("\\familyname{{\\LARGE {last_name}}}"));
         } else {
             lines.push("\\familyname{{}}".into());
         }
         if let Some(title) = &self.title {
-            lines.push(format!("\\title{{{}}}", title));
+            lines.push(format!("\\title{{{}}}", escape_latex(title)));
         }
         for t in &self.mobile {
-            lines.push(format!("\\mobile{{{}}}", t));
+            lines.push(format!("\\mobile{{{}}}", escape_latex(t)));
         }
-        for e in &self.email {
-            lines.push(format!("\\email{{{}}}", e.mail));
+        // moderncv shows a single address, the others are extra information
+        if let Some((first, others)) = self.email.split_first() {
+            lines.push(format!("\\email{{{}}}", escape_latex(&first.mail)));
+            for e in others {
+                lines.push(format!("\\extrainfo{{{}}}", e.to_latex()));
+            }
         }
         // socials
         if let Some(e) = &self.github {
-            lines.push(format!("\\social[github]{{{e}}}"))
+            lines.push(format!("\\social[github]{{{}}}", escape_latex(e)))
         }
         if let Some(e) = &self.gitlab {
-            lines.push(format!("\\social[gitlab]{{{e}}}"))
+            lines.push(format!("\\social[gitlab]{{{}}}", escape_latex(e)))
         }
         if let Some(e) = &self.linkedin {
-            lines.push(format!("\\social[linkedin]{{{e}}}"))
+            lines.push(format!("\\social[linkedin]{{{}}}", escape_latex(e)))
         }
         if let Some(e) = &self.twitter {
-            lines.push(format!("\\social[twitter]{{{e}}}"))
+            lines.push(format!("\\social[twitter]{{{}}}", escape_latex(e)))
         }
         for (n, u) in &self.webpage {
+            let n = escape_latex(n);
+            let u = escape_url(u);
             lines.push(format!("\\extrainfo{{\\homepagesymbol {n} \\url{{{u}}}}}"));
         }
         lines.join("\n")
@@ -471,9 +1067,9 @@ impl PersonalData {
 #[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 struct CVLanguage {
     language: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     level: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     comment: String,
 }
 
@@ -481,24 +1077,34 @@ impl CVLanguage {
     fn to_latex(&self) -> String {
         format!(
             "\\cvlanguage{{{}}}{{{}}}{{{}}}",
-            self.language, self.level, self.comment
+            escape_latex(&self.language),
+            escape_latex(&self.level),
+            escape_latex(&self.comment)
         )
     }
 }
 #[derive(Debug)]
-struct List(Vec<String>);
-
-/// create the first page
-///
-/// The fisrt page should sum up the resume, including
-/// * technical knowledge (ventilated by experience?)
-/// * functional knowledge
-/// * industry knowledge (in which industry your work in)
-fn make_first_page() -> String {
-    todo!()
+struct List(Vec<Text>);
+
+/// period of time, (beginning, end)
+type Interval = (DateTime<Utc>, DateTime<Utc>);
+
+/// total duration of the intervals, overlapping periods being counted once
+fn covered_duration(mut intervals: Vec<Interval>) -> Duration {
+    intervals.sort();
+    let mut total = Duration::zero();
+    let mut covered_until: Option<DateTime<Utc>> = None;
+    for (beginning, end) in intervals {
+        let beginning = covered_until.map_or(beginning, |until| beginning.max(until));
+        if end > beginning {
+            total += end - beginning;
+            covered_until = Some(end);
+        }
+    }
+    total
 }
 
-#[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[derive(Serialize, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct CVDuration {
     pub year: u32,
     pub month: u32,
@@ -540,6 +1146,56 @@ impl CVDuration {
             }
         }
     }
+
+    /// Duration in fractional years
+    /// ```
+    /// use curriculum::CVDuration;
+    ///
+    /// assert_eq!(CVDuration {year: 2, month: 3}.as_years(), 2.25);
+    /// ```
+    pub fn as_years(&self) -> f64 {
+        f64::from(self.year) + f64::from(self.month) / 12.0
+    }
+}
+
+impl From<Duration> for CVDuration {
+    /// Convert to years and months, negative durations being null
+    /// ```
+    /// use chrono::Duration;
+    /// use curriculum::CVDuration;
+    ///
+    /// let d = CVDuration::from(Duration::days(400));
+    /// assert_eq!(d, CVDuration {year: 1, month: 1});
+    ///
+    /// let d = CVDuration::from(Duration::days(364));
+    /// assert_eq!(d, CVDuration {year: 1, month: 0});
+    /// ```
+    fn from(duration: Duration) -> Self {
+        let duration: u32 = duration.num_days().max(0) as u32;
+        let year = duration / 365;
+        let remaining_days = duration % 365;
+        let month = (remaining_days + 15) / 30;
+        // the last days of a year round up to a whole year
+        Self { year, month: 0 } + Self { year: 0, month }
+    }
+}
+
+impl fmt::Display for CVDuration {
+    /// ```
+    /// use curriculum::CVDuration;
+    ///
+    /// assert_eq!(CVDuration {year: 1, month: 0}.to_string(), "1 year");
+    /// assert_eq!(CVDuration {year: 3, month: 1}.to_string(), "3 years 1 month");
+    /// assert_eq!(CVDuration {year: 0, month: 0}.to_string(), "0 months");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = |n: u32| if n > 1 { "s" } else { "" };
+        match (self.year, self.month) {
+            (0, m) => write!(f, "{m} month{}", if m == 1 { "" } else { "s" }),
+            (y, 0) => write!(f, "{y} year{}", plural(y)),
+            (y, m) => write!(f, "{y} year{} {m} month{}", plural(y), plural(m)),
+        }
+    }
 }
 
 impl Add for CVDuration {
@@ -572,8 +1228,8 @@ impl Add for CVDuration {
 }
 
 /// Get LaTeX for small paragraph to be inserted in job description
-fn get_titled_description(title: &str, content: &str, tags: &mut HashSet<String>) -> String {
-    let tag = normalize_tag(title.into());
+fn get_titled_description(title: &str, content: &str, tags: &mut BTreeSet<String>) -> String {
+    let tag = normalize_tag(title);
     tags.insert(tag.clone());
     let mut lines = Vec::new();
     lines.push("%".into());
@@ -600,62 +1256,145 @@ impl List {
             &self
                 .0
                 .iter()
-                .map(|elt| format!("\\item {}", elt))
+                .map(|elt| format!("\\item {}", elt.to_latex()))
                 .collect::<Vec<_>>()
                 .join("\n")
         )
     }
-    fn get_titled_description(&self, title: &str, tags: &mut HashSet<String>) -> String {
+    fn get_titled_description(&self, title: &str, tags: &mut BTreeSet<String>) -> String {
         get_titled_description(title, &self.to_latex(), tags)
     }
 }
 
 mod cv_date {
-    use chrono::{DateTime, TimeZone, Utc};
-    use serde::{self, Deserialize, Deserializer, Serializer};
-    const FORMAT: &str = "%Y-%m";
+    use chrono::{DateTime, Datelike, TimeZone, Utc};
+    use serde::{self, de, Deserialize, Deserializer, Serializer};
+    use std::fmt;
+    use std::str::FromStr;
+
+    /// Precision of a date, as written in the source
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum Precision {
+        Year,
+        Month,
+        Day,
+    }
+
+    /// Date of a curriculum entry
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CVDate {
+        /// date known up to the precision
+        Date(DateTime<Utc>, Precision),
+        /// ongoing, written `present` or `now`
+        Present,
+    }
+
+    impl CVDate {
+        /// the date, None if ongoing
+        pub fn date(&self) -> Option<DateTime<Utc>> {
+            match self {
+                Self::Date(date, _) => Some(*date),
+                Self::Present => None,
+            }
+        }
+
+        /// year to display
+        pub fn year(&self) -> String {
+            match self {
+                Self::Date(date, _) => date.year().to_string(),
+                Self::Present => "present".into(),
+            }
+        }
+    }
+
+    impl FromStr for CVDate {
+        type Err = String;
+
+        /// parse `YYYY`, `YYYY-MM`, `YYYY-MM-DD`, `present` or `now`
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let s = s.trim();
+            if s.eq_ignore_ascii_case("present") || s.eq_ignore_ascii_case("now") {
+                return Ok(Self::Present);
+            }
+            let invalid =
+                || format!("invalid date `{s}`, expected YYYY, YYYY-MM, YYYY-MM-DD or present");
+            let parts = s
+                .split('-')
+                .map(str::parse::<u32>)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid())?;
+            let (year, month, day, precision) = match parts[..] {
+                [y] => (y, 1, 1, Precision::Year),
+                [y, m] => (y, m, 1, Precision::Month),
+                [y, m, d] => (y, m, d, Precision::Day),
+                _ => return Err(invalid()),
+            };
+            let year = i32::try_from(year).map_err(|_| invalid())?;
+            let date = Utc
+                .with_ymd_and_hms(year, month, day, 0, 0, 0)
+                .single()
+                .ok_or_else(invalid)?;
+            Ok(Self::Date(date, precision))
+        }
+    }
 
-    pub fn serialize<S>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    impl fmt::Display for CVDate {
+        /// format with the original precision
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Self::Date(date, Precision::Year) => write!(f, "{}", date.format("%Y")),
+                Self::Date(date, Precision::Month) => write!(f, "{}", date.format("%Y-%m")),
+                Self::Date(date, Precision::Day) => write!(f, "{}", date.format("%Y-%m-%d")),
+                Self::Present => write!(f, "present"),
+            }
+        }
+    }
+
+    /// date as found in the source, years may be written as numbers
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawDate {
+        Text(String),
+        Year(u32),
+    }
+
+    pub fn serialize<S>(date: &Option<CVDate>, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
         if let Some(date) = date {
-            let s = format!("{}", date.format(FORMAT));
-            serializer.serialize_str(&s)
+            serializer.serialize_str(&date.to_string())
         } else {
             serializer.serialize_none()
         }
     }
 
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<CVDate>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s: Option<String> = Option::deserialize(deserializer)?;
-        if let Some(s) = s {
-            let date: Vec<_> = s.split('-').collect();
-            let date: DateTime<Utc> = Utc
-                .with_ymd_and_hms(
-                    str::parse(date[0]).unwrap(),
-                    str::parse(date[1]).unwrap(),
-                    1,
-                    0,
-                    0,
-                    0,
-                )
-                .unwrap();
-            Ok(Some(date))
-        } else {
-            Ok(None)
+        let raw: Option<RawDate> = Option::deserialize(deserializer)?;
+        match raw {
+            Some(RawDate::Text(s)) => s.parse().map(Some).map_err(de::Error::custom),
+            Some(RawDate::Year(y)) => y.to_string().parse().map(Some).map_err(de::Error::custom),
+            None => Ok(None),
         }
     }
 }
 
 #[cfg(test)]
+#[allow(unused_variables, clippy::needless_borrow)] // original tests
 mod tests {
     use super::*;
+    use chrono::TimeZone;
     use regex::Regex;
 
+    /// duration of the entry, ongoing entries ending on 2024-01-01
+    fn cv_duration(entry: &CVEntry) -> Option<CVDuration> {
+        let today = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        entry.span(today).map(|(b, e)| CVDuration::from(e - b))
+    }
+
     #[test]
     fn read_incomplete_entry() {
         let entry = r#"
@@ -706,9 +1445,33 @@ mod tests {
         let result = personal_data.to_latex();
     }
 
+    #[test]
+    fn escape_personal_data() {
+        let data = r#"
+        {
+            "name": "John Doe",
+            "email": [
+                {"mail": "first_last@x.org"},
+                {"name": "R&D", "mail": "r_d@x.org"},
+                {"mail": "a_b@x.org"}
+            ],
+            "github": "john_doe",
+            "twitter": "john_doe",
+            "webpage": [["blog", "https://x.org/#top"]]
+        }"#;
+        let personal_data: PersonalData = serde_json::from_str(data).unwrap();
+        let result = personal_data.to_latex();
+        assert!(result.contains("\\email{first\\_last@x.org}\n"));
+        assert!(result.contains("\\extrainfo{R\\&D: \\href{mailto:r_d@x.org}{r\\_d@x.org}}"));
+        assert!(result.contains("\\extrainfo{\\href{mailto:a_b@x.org}{a\\_b@x.org}}"));
+        assert!(result.contains("\\social[github]{john\\_doe}\n"));
+        assert!(result.contains("\\social[twitter]{john\\_doe}"));
+        assert!(result.contains("\\url{https://x.org/\\#top}"));
+    }
+
     #[test]
     fn description_tex() {
-        let mut tags = HashSet::new();
+        let mut tags = BTreeSet::new();
         let data = r#"
         {
             "context": "some super context",
@@ -716,8 +1479,51 @@ mod tests {
         }
         "#;
         let entry: EntryDescription = serde_json::from_str(&data).unwrap();
-        let result = entry.to_latex(&mut tags);
-        assert!(tags.contains("context".into()))
+        let result = entry.to_latex(&SkillCategory::defaults(), &mut tags);
+        assert!(tags.contains("context"))
+    }
+
+    #[test]
+    fn latex_escaping() {
+        let data = r#"
+        {
+            "degree": "R&D engineer",
+            "institution": "Campbell, Delgado & Parker",
+            "city": "~home",
+            "description": {
+                "context": "first_line\nsecond {line}",
+                "tasks": ["100% uptime", {"latex": "\\textbf{bold} \\& raw"}],
+                "programming": ["C#", "$hell"]
+            }
+        }
+        "#;
+        let entry: CVEntry = serde_json::from_str(data).unwrap();
+        let tex = entry.to_latex(
+            None,
+            &SkillCategory::defaults(),
+            &TagNames::default(),
+            &mut BTreeSet::new(),
+        );
+        assert!(
+            tex.contains("{R\\&D engineer}{Campbell, Delgado \\& Parker}{\\textasciitilde{}home}")
+        );
+        assert!(tex.contains("first\\_line\\\\%\nsecond \\{line\\}"));
+        assert!(tex.contains("\\item 100\\% uptime"));
+        assert!(tex.contains("\\item \\textbf{bold} \\& raw"));
+        let raw = entry.description.as_ref().unwrap().tasks[1].clone();
+        assert_eq!(raw.to_plain(), "bold & raw");
+        assert_eq!(
+            strip_latex("\\LaTeX{} rocks~\\\\ 50\\% % comment"),
+            "rocks \n 50%"
+        );
+        assert!(tex.contains("C\\#, \\$hell"));
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["description"]["tasks"][0], "100% uptime");
+        assert_eq!(
+            json["description"]["tasks"][1]["latex"],
+            "\\textbf{bold} \\& raw"
+        );
     }
 
     #[test]
@@ -735,7 +1541,7 @@ mod tests {
         "#;
         let entry: CVEntry = serde_json::from_str(&data).unwrap();
         let skills = entry.extract_skills();
-        assert!(skills.keys().collect::<Vec<_>>().contains(&&"CI/CD"));
+        assert!(skills.keys().collect::<Vec<_>>().contains(&&"ci"));
     }
 
     #[test]
@@ -753,7 +1559,7 @@ mod tests {
         }
         "#;
         let entry: CVEntry = serde_json::from_str(&data).unwrap();
-        let duration = entry.cv_duration();
+        let duration = cv_duration(&entry);
         assert_eq!(duration, Some(CVDuration { year: 0, month: 2 }));
     }
 
@@ -772,7 +1578,7 @@ mod tests {
         }
         "#;
         let entry: CVEntry = serde_json::from_str(&data).unwrap();
-        let duration = entry.cv_duration();
+        let duration = cv_duration(&entry);
         assert_eq!(duration, Some(CVDuration { year: 10, month: 2 }));
     }
 
@@ -791,7 +1597,7 @@ mod tests {
         }
         "#;
         let entry: CVEntry = serde_json::from_str(&data).unwrap();
-        let duration = entry.cv_duration();
+        let duration = cv_duration(&entry);
         assert_eq!(duration, Some(CVDuration { year: 0, month: 0 }));
     }
 
@@ -808,7 +1614,7 @@ mod tests {
         }
         "#;
         let entry: CVEntry = serde_json::from_str(&data).unwrap();
-        let duration = entry.cv_duration();
+        let duration = cv_duration(&entry);
         assert_eq!(duration, None);
     }
 
@@ -826,10 +1632,54 @@ mod tests {
         }
         "#;
         let entry: CVEntry = serde_json::from_str(&data).unwrap();
-        let duration = entry.cv_duration().unwrap();
+        let duration = cv_duration(&entry).unwrap();
         assert!(duration.month + duration.year > 0);
     }
 
+    #[test]
+    fn date_precision_round_trip() {
+        let data = r#"
+        {
+            "beginning": "2020",
+            "end": "2021-03-15",
+            "subentries": [{"beginning": "2020-07", "end": "now"}]
+        }
+        "#;
+        let entry: CVEntry = serde_json::from_str(data).unwrap();
+        assert_eq!(entry.get_dates(), "2020--2021");
+        assert_eq!(entry.subentries[0].get_dates(), "2020--present");
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["beginning"], "2020");
+        assert_eq!(json["end"], "2021-03-15");
+        assert_eq!(json["subentries"][0]["beginning"], "2020-07");
+        assert_eq!(json["subentries"][0]["end"], "present");
+    }
+
+    #[test]
+    fn date_ongoing() {
+        let data = r#"{"beginning": 2020, "end": "Present"}"#;
+        let entry: CVEntry = serde_json::from_str(data).unwrap();
+        assert_eq!(entry.end, Some(CVDate::Present));
+        let duration = cv_duration(&entry);
+        assert_eq!(duration, Some(CVDuration { year: 4, month: 0 }));
+    }
+
+    #[test]
+    fn invalid_dates() {
+        for date in [
+            "2020-13",
+            "2020-02-30",
+            "20-ab",
+            "",
+            "2020-01-01-01",
+            "soon",
+        ] {
+            let data = format!(r#"{{"beginning": "{date}"}}"#);
+            let err = serde_json::from_str::<CVEntry>(&data).unwrap_err();
+            assert!(err.to_string().contains("invalid date"), "{err}");
+        }
+    }
+
     #[test]
     fn get_cv_skills() {
         let data = r#"
@@ -868,98 +1718,331 @@ mod tests {
         "#;
         let cv: Curriculum = serde_json::from_str(&data).unwrap();
         let s = cv.get_skills();
-        assert_eq!(s["CI/CD"]["git"].clone(), CVDuration { year: 1, month: 11 });
+        assert_eq!(s["ci"]["git"].calendar, CVDuration { year: 1, month: 2 });
+        assert_eq!(s["ci"]["git"].cumulative, CVDuration { year: 1, month: 11 });
         assert_eq!(
-            s["cloud computing"]["azure"],
+            s["cloud"]["azure"].calendar,
             CVDuration { year: 0, month: 10 }
         );
     }
 
     #[test]
-    fn write_email() {
+    fn entry_tags() {
         let data = r#"
-        [
-            {
-                "name": "John",
-                "mail": "j@doe.org"
-            },
-            {
-                "mail": "blah@example.com"
-            }
-        ]
+        {
+            "beginning": "2018-01",
+            "institution": "Lee & Sons",
+            "tags": ["public-sector"],
+            "subentries": [{"institution": "client", "tags": ["backend"]}]
+        }
         "#;
-        let emails: Vec<CVEmail> = serde_json::from_str(&data).unwrap();
-        let tex: Vec<String> = emails.iter().map(|e| e.to_latex()).collect();
-        assert!(tex[0].contains("mailto:"));
-        assert!(tex[0].contains("John"));
-        assert!(tex[1].contains("mailto:"));
+        let entry: CVEntry = serde_json::from_str(data).unwrap();
+        let mut tags = BTreeSet::new();
+        let tex = entry.to_latex(
+            None,
+            &SkillCategory::defaults(),
+            &TagNames::default(),
+            &mut tags,
+        );
+        assert!(tex.starts_with("\\ifpublicsector% beginning of publicsector\n\\cventry"));
+        assert!(tex.contains("\\ifbackend% beginning of backend\n\\hspace*{-21.5ex}\\cventry"));
+        assert_eq!(
+            tags,
+            BTreeSet::from(["publicsector".into(), "backend".into()])
+        );
     }
 
     #[test]
-    fn skills_accumulator() {
-        let mut skills0 = HashMap::new();
-        let skills1 = HashMap::from([
-            (
-                "languages",
-                HashMap::from([
-                    ("english", Duration::days(20)),
-                    ("spanish", Duration::days(30)),
-                    ("french", Duration::days(1)),
-                ]),
-            ),
-            ("management", HashMap::from([("jira", Duration::zero())])),
-        ]);
-
-        add_skillsets(&mut skills0, skills1);
-        assert_eq!(
-            skills0.get("languages").and_then(|s| s.get("english")),
-            Some(Duration::days(20)).as_ref()
-        );
+    fn first_page() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [
+                {
+                    "beginning": "2015-01",
+                    "end": "2018-01",
+                    "degree": "developer",
+                    "institution": "Brewer LLC",
+                    "industry": "energy",
+                    "description": {"programming": ["python"], "ci": ["git"]}
+                },
+                {
+                    "beginning": "2018-01",
+                    "end": "2020-01",
+                    "institution": "Lee & Sons",
+                    "industry": "Retail",
+                    "description": {"programming": ["rust", "python"]},
+                    "subentries": [{"industry": "health"}]
+                },
+                {
+                    "beginning": "2019-01",
+                    "end": "2021-01",
+                    "degree": "teacher",
+                    "industry": "energy",
+                    "part time": true
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
         assert_eq!(
-            skills0.get("management").and_then(|s| s.get("jira")),
-            Some(Duration::zero()).as_ref()
+            cv.industries(),
+            vec![
+                &Industry::Energy,
+                &Industry::Other("Retail".into()),
+                &Industry::Health
+            ]
         );
+        let mut tags = BTreeSet::new();
+        let page = cv.make_first_page(&LatexOptions::default(), &mut tags);
+        assert!(tags.contains("firstpage"));
+        assert!(page.contains("\\cvitem{experience}{6 years}"));
+        assert!(page.contains("\\cvitem{industries}{energy, Retail, health}"));
+        assert!(page.contains("\\cvitem{programming languages}{python (5 years), rust (2 years)}"));
+        assert!(page.contains(
+            "\\subsection{Recent roles}\n\\cvitem{2019--2021}{teacher}\n\\cvitem{2018--2020}{Lee \\& Sons}"
+        ));
+        assert!(page.ends_with("\\clearpage\n\\fi% ---- end   first page"));
+    }
 
-        let skills2 = HashMap::from([
-            (
-                "languages",
-                HashMap::from([
-                    ("arabic", Duration::days(3)),
-                    ("french", Duration::days(10)),
-                ]),
-            ),
-            ("driving", HashMap::from([("cars", Duration::zero())])),
-        ]);
-        add_skillsets(&mut skills0, skills2);
+    #[test]
+    fn optional_packages() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [
+                {
+                    "beginning": "2015-01",
+                    "end": "2018-01",
+                    "description": {"programming": ["python"]}
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let tex = cv.to_latex_with(&LatexOptions::default()).unwrap();
+        assert!(tex.contains("\\usepackage{tikz}"));
+        assert!(tex.contains("\\usepackage{pgfplots}"));
+
+        let options = LatexOptions {
+            tags: TagProfile {
+                disable: vec!["timeline".into()],
+                ..Default::default()
+            },
+            chart_skills: 0,
+            ..Default::default()
+        };
+        let tex = cv.to_latex_with(&options).unwrap();
+        assert!(!tex.contains("\\usepackage{tikz}"));
+        assert!(!tex.contains("\\usepackage{pgfplots}"));
+
+        let options = LatexOptions {
+            tags: TagProfile {
+                disable: vec!["skillchart".into()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let tex = cv.to_latex_with(&options).unwrap();
+        assert!(tex.contains("\\usepackage{tikz}"));
+        assert!(!tex.contains("\\usepackage{pgfplots}"));
+
+        let cv: Curriculum = serde_json::from_str(
+            r#"{"personal data": {"name": "Jessica"}, "education": [], "experiences": []}"#,
+        )
+        .unwrap();
+        let tex = cv.to_latex_with(&LatexOptions::default()).unwrap();
+        assert!(!tex.contains("\\usepackage{tikz}"));
+        assert!(!tex.contains("\\usepackage{pgfplots}"));
+    }
+
+    #[test]
+    fn skills_section() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [
+                {
+                    "beginning": "2015-01",
+                    "end": "2018-01",
+                    "description": {"programming": ["python", "C#"], "ci": ["git"]}
+                },
+                {
+                    "beginning": "2018-01",
+                    "end": "2018-05",
+                    "description": {"programming": ["rust", "python"], "ci": ["jenkins"]}
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let mut tags = BTreeSet::new();
+        let tex = cv.skills_to_latex(&LatexOptions::default(), &mut tags);
+        assert!(tags.contains("skills"));
+        assert!(tex.contains(
+            "\\cvitem{programming languages}{python (3 years), C\\# (3 years), rust (4 months)}\n\\cvitem{CI/CD}{git (3 years), jenkins (4 months)}"
+        ));
+
+        let options = LatexOptions {
+            min_skill_duration: CVDuration { year: 0, month: 6 },
+            max_skills_per_category: Some(1),
+            ..Default::default()
+        };
+        let tex = cv.skills_to_latex(&options, &mut tags);
+        assert!(tex.contains("\\cvitem{programming languages}{python (3 years)}\n"));
+        assert!(tex.contains("\\cvitem{CI/CD}{git (3 years)}\n"));
+    }
+
+    #[test]
+    fn subentries_skills() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "experiences": [
+                {
+                    "beginning": "2018-01",
+                    "end": "2022-01",
+                    "description": {"ci": ["git"]},
+                    "subentries": [
+                        {
+                            "beginning": "2018-01",
+                            "end": "2019-01",
+                            "description": {"ci": ["git"], "database": ["postgresql"]}
+                        },
+                        {
+                            "beginning": "2021-01",
+                            "description": {"database": ["postgresql"]}
+                        },
+                        {"description": {"other": ["ansible"]}}
+                    ]
+                }
+            ],
+            "education": [
+                {
+                    "beginning": "2014-09",
+                    "end": "2017-09",
+                    "description": {"database": ["postgresql"]}
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let s = cv.get_skills();
         assert_eq!(
-            skills0.get("languages").and_then(|s| s.get("english")),
-            Some(Duration::days(20)).as_ref()
+            s["database"]["postgresql"],
+            SkillExperience {
+                calendar: CVDuration { year: 2, month: 0 },
+                cumulative: CVDuration { year: 2, month: 0 },
+                last_used: Some(2022),
+            }
         );
         assert_eq!(
-            skills0.get("languages").and_then(|s| s.get("spanish")),
-            Some(Duration::days(30)).as_ref()
+            s["ci"]["git"],
+            SkillExperience {
+                calendar: CVDuration { year: 4, month: 0 },
+                cumulative: CVDuration { year: 5, month: 0 },
+                last_used: Some(2022),
+            }
         );
         assert_eq!(
-            skills0.get("languages").and_then(|s| s.get("french")),
-            Some(Duration::days(11)).as_ref()
+            s["other"]["ansible"].calendar,
+            CVDuration { year: 4, month: 0 }
         );
+
+        let options = SkillOptions {
+            include_education: true,
+            ..Default::default()
+        };
+        let s = cv.get_skills_with(&options);
         assert_eq!(
-            skills0.get("languages").and_then(|s| s.get("arabic")),
-            Some(Duration::days(3)).as_ref()
+            s["database"]["postgresql"].calendar,
+            CVDuration { year: 5, month: 0 }
         );
+    }
+
+    #[test]
+    fn custom_skill_categories() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "skill categories": [
+                {"name": "observability", "display name": "Observability & alerting"},
+                {"name": "ci"}
+            ],
+            "education": [],
+            "experiences": [
+                {
+                    "beginning": "2020-01",
+                    "end": "2022-01",
+                    "description": {
+                        "ci": ["git"],
+                        "programming": ["rust"],
+                        "skills": {
+                            "ci": ["git", "jenkins"],
+                            "observability": ["prometheus"],
+                            "security": ["vault"]
+                        }
+                    }
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let categories = cv.skill_categories();
+        let names = categories
+            .iter()
+            .map(|c| (c.name.as_str(), c.display_name()))
+            .collect::<Vec<_>>();
         assert_eq!(
-            skills0.get("management").and_then(|s| s.get("jira")),
-            Some(Duration::zero()).as_ref()
+            names,
+            vec![
+                ("observability", "Observability & alerting"),
+                ("ci", "CI/CD"),
+                ("programming", "programming languages"),
+                ("security", "security"),
+            ]
         );
         assert_eq!(
-            skills0.get("driving").and_then(|s| s.get("cars")),
-            Some(Duration::zero()).as_ref()
+            cv.experiences[0].extract_skills()["ci"],
+            vec!["git", "jenkins"]
+        );
+        let tex = cv.experiences[0].to_latex(
+            None,
+            &categories,
+            &TagNames::default(),
+            &mut BTreeSet::new(),
         );
+        assert!(tex.contains(
+            "\\item [Observability \\& alerting] prometheus\n\\item [CI/CD] git, jenkins\n\\item [programming languages] rust\n\\item [security] vault"
+        ));
+    }
+
+    #[test]
+    fn write_email() {
+        let data = r#"
+        [
+            {
+                "name": "John",
+                "mail": "j@doe.org"
+            },
+            {
+                "mail": "blah@example.com"
+            }
+        ]
+        "#;
+        let emails: Vec<CVEmail> = serde_json::from_str(&data).unwrap();
+        let tex: Vec<String> = emails.iter().map(|e| e.to_latex()).collect();
+        assert!(tex[0].contains("mailto:"));
+        assert!(tex[0].contains("John"));
+        assert!(tex[1].contains("mailto:"));
     }
 
     #[test]
     fn subentries() {
-        let mut tags = HashSet::new();
+        let mut tags = BTreeSet::new();
         let data = r#"
         {
             "beginning": "1977-07-01",
@@ -988,7 +2071,12 @@ mod tests {
         }
         "#;
         let entry: CVEntry = serde_json::from_str(&data).unwrap();
-        let tex = entry.to_latex(None, &mut tags);
+        let tex = entry.to_latex(
+            None,
+            &SkillCategory::defaults(),
+            &TagNames::default(),
+            &mut tags,
+        );
         assert_eq!(
             tex.chars().filter(|&x| x == '{').count(),
             tex.chars().filter(|&x| x == '}').count()
@@ -1,31 +1,520 @@
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use curriculum::{
+    reference_date, CVDate, CVDuration, Curriculum, EntryFilter, LatexOptions, SkillAliases,
+    SkillOptions, SkillSort, SourceFormat, TagProfile, TechnologyDictionary, TimelineOptions,
+    UnknownFields, ValidationOptions,
+};
 use std::fs;
-use std::path::Path;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 #[cfg(feature = "pdf")]
 use tectonic;
 
+#[derive(ValueEnum, Clone, Debug)]
+enum Format {
+    /// LaTeX document
+    Tex,
+    /// self-contained HTML page
+    Html,
+    /// Markdown document
+    Markdown,
+    /// JSON Resume (https://jsonresume.org/schema)
+    JsonResume,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum ReportFormat {
+    /// table aligned for a terminal
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(clap::Args, Debug)]
+struct Source {
+    /// input filename
+    input: String,
+    /// input format, guessed from the file extension if not given
+    #[arg(short, long)]
+    input_format: Option<SourceFormat>,
+    /// fail on unknown fields instead of ignoring them
+    #[arg(long)]
+    strict: bool,
+    /// rename skills to their canonical spelling
+    #[arg(long)]
+    canonicalize: bool,
+    /// list the spellings merged by --canonicalize
+    #[arg(long, requires = "canonicalize")]
+    show_merges: bool,
+    /// file of skill aliases {canonical: [aliases]}, completing the built-in
+    /// ones of --canonicalize and --skill
+    #[arg(long)]
+    aliases: Option<PathBuf>,
+    /// date of the "until now" computations (YYYY, YYYY-MM or YYYY-MM-DD),
+    /// SOURCE_DATE_EPOCH or today by default
+    #[arg(long)]
+    as_of: Option<CVDate>,
+}
+
+impl Source {
+    /// given format, or the one guessed from the file extension
+    fn format(&self) -> Result<SourceFormat> {
+        self.input_format
+            .or_else(|| SourceFormat::from_path(Path::new(&self.input)))
+            .ok_or_else(|| anyhow!("cannot guess format of {}", self.input))
+    }
+
+    /// date of the "until now" computations
+    fn today(&self) -> DateTime<Utc> {
+        self.as_of
+            .and_then(|date| date.date())
+            .unwrap_or_else(reference_date)
+    }
+
+    /// built-in aliases, completed by the aliases file
+    fn aliases(&self) -> Result<SkillAliases> {
+        let mut aliases = SkillAliases::builtin();
+        if let Some(path) = &self.aliases {
+            aliases.extend_from_path(path)?;
+        }
+        Ok(aliases)
+    }
+
+    /// read the curriculum as written, failing on unknown fields
+    fn read_as_is(&self) -> Result<Curriculum> {
+        if self.canonicalize {
+            bail!("skills are written as in the source, --canonicalize does not apply");
+        }
+        let path = Path::new(&self.input);
+        let (cv, unknown) = Curriculum::from_path_checked(path, self.input_format)?;
+        if !unknown.is_empty() {
+            return Err(UnknownFields(unknown)).context("these fields would be lost");
+        }
+        Ok(cv)
+    }
+
+    fn read(&self) -> Result<Curriculum> {
+        let path = Path::new(&self.input);
+        let (mut cv, unknown) = Curriculum::from_path_checked(path, self.input_format)?;
+        if self.strict && !unknown.is_empty() {
+            return Err(UnknownFields(unknown).into());
+        }
+        for field in unknown {
+            eprintln!("warning: {field}");
+        }
+        if self.canonicalize {
+            let report = cv.canonicalize_skills(&self.aliases()?);
+            if self.show_merges {
+                eprint!("{report}");
+            }
+        }
+        Ok(cv)
+    }
+}
+
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)] // parsed once
+enum Command {
+    /// Render the curriculum as LaTeX, HTML, Markdown or JSON Resume
+    Render {
+        #[command(flatten)]
+        source: Source,
+        /// output format
+        #[arg(short, long, value_enum, default_value_t = Format::Tex)]
+        format: Format,
+        /// output file, `-` for the standard output, next to the input by default
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        latex: LatexArgs,
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    /// Check the chronology of entries
+    Validate {
+        #[command(flatten)]
+        source: Source,
+        /// longest gap between experiences not reported, in months
+        #[arg(long, default_value_t = 6)]
+        max_gap: u32,
+    },
+    /// Report skills with their duration and the year they were last used
+    Skills {
+        #[command(flatten)]
+        source: Source,
+        /// report format
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+        /// order of the skills: category, duration or name
+        #[arg(long, default_value = "category")]
+        sort: SkillSort,
+        /// report only this category, by name or display name (repeatable)
+        #[arg(long = "category")]
+        categories: Vec<String>,
+        /// count skills of education entries as experience
+        #[arg(long)]
+        education_skills: bool,
+    },
+    /// Convert the curriculum to another source format, failing if a field
+    /// would be lost
+    Convert {
+        #[command(flatten)]
+        source: Source,
+        /// output format, guessed from the output file extension if not given
+        #[arg(short, long)]
+        to: Option<SourceFormat>,
+        /// output file, the standard output by default
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Summarize the curriculum
+    Stats {
+        #[command(flatten)]
+        source: Source,
+    },
+    /// Draw the entries as a timeline across the years
+    Timeline {
+        #[command(flatten)]
+        source: Source,
+        /// width of the timeline, in characters
+        #[arg(long, default_value_t = 80)]
+        width: usize,
+    },
+    /// List skills of `other` whose category is known
+    Categorize {
+        #[command(flatten)]
+        source: Source,
+        /// move the skills to their category and write the curriculum to this
+        /// file, `-` for the standard output (comments are not kept)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List the LaTeX conditional of each institution and entry tag
+    Tags {
+        #[command(flatten)]
+        source: Source,
+    },
+}
+
 #[derive(Parser, Debug)]
 #[command(author, about)]
 struct Args {
-    /// input filename
-    input: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Args, Debug)]
+struct FilterArgs {
+    /// remove entries ending before this date (YYYY, YYYY-MM or YYYY-MM-DD)
+    #[arg(long)]
+    since: Option<CVDate>,
+    /// remove entries beginning after this date (YYYY, YYYY-MM or YYYY-MM-DD)
+    #[arg(long)]
+    until: Option<CVDate>,
+    /// keep only experiences of this institution (repeatable)
+    #[arg(long = "institution")]
+    institutions: Vec<String>,
+    /// remove entries of this institution (repeatable)
+    #[arg(long = "exclude-institution")]
+    exclude_institutions: Vec<String>,
+    /// keep only experiences using this skill, or one of its aliases (repeatable)
+    #[arg(long = "skill")]
+    skills: Vec<String>,
+    /// keep only experiences having this tag (repeatable)
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// remove entries having this tag (repeatable)
+    #[arg(long = "exclude-tag")]
+    exclude_tags: Vec<String>,
+    /// keep only experiences of at least this priority
+    #[arg(long, allow_negative_numbers = true)]
+    min_priority: Option<i32>,
+    /// list experiences by decreasing priority
+    #[arg(long)]
+    sort_by_priority: bool,
+}
+
+impl FilterArgs {
+    fn filter(&self, today: DateTime<Utc>, aliases: SkillAliases) -> EntryFilter {
+        EntryFilter {
+            since: self.since.map(|d| d.date().unwrap_or(today)),
+            until: self.until.map(|d| d.date().unwrap_or(today)),
+            institutions: self.institutions.clone(),
+            exclude_institutions: self.exclude_institutions.clone(),
+            skills: self.skills.clone(),
+            aliases,
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            min_priority: self.min_priority,
+            today,
+        }
+    }
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
-    let path = Path::new(&args.input);
+#[derive(clap::Args, Debug)]
+struct LatexArgs {
+    /// shortest skill duration listed in the skills section, in months
+    #[arg(long, default_value_t = 0)]
+    min_skill_duration: u32,
+    /// maximum number of skills per category in the skills section
+    #[arg(long)]
+    max_skills: Option<usize>,
+    /// count skills of education entries as experience
+    #[arg(long)]
+    education_skills: bool,
+    /// profile of the conditional tags, from the profile file or the curriculum
+    #[arg(long)]
+    profile: Option<String>,
+    /// file of named tag profiles {name: {default, enable, disable}}
+    #[arg(long, requires = "profile")]
+    profile_file: Option<PathBuf>,
+    /// number of skills of the skill chart
+    #[arg(long, default_value_t = 10)]
+    chart_skills: usize,
+}
 
-    let content = fs::read_to_string(path)?;
-    let cv: curriculum::Curriculum = serde_json::from_str(&content)?;
-    let tex_data = cv.to_latex()?;
-    let tex_path = path.with_extension("tex");
-    println!("writing {}", tex_path.display());
-    let _ = fs::write(&tex_path, tex_data);
+impl LatexArgs {
+    fn options(&self, cv: &Curriculum, today: DateTime<Utc>) -> Result<LatexOptions> {
+        let tags = match &self.profile {
+            Some(name) => {
+                let mut profiles = match &self.profile_file {
+                    Some(path) => TagProfile::read_profiles(path)?,
+                    None => Default::default(),
+                };
+                match profiles.remove(name) {
+                    Some(profile) => profile,
+                    None => cv
+                        .tag_profile(name)
+                        .cloned()
+                        .ok_or_else(|| anyhow!("unknown tag profile {name}"))?,
+                }
+            }
+            None => TagProfile::default(),
+        };
+        Ok(LatexOptions {
+            min_skill_duration: months(self.min_skill_duration),
+            max_skills_per_category: self.max_skills,
+            education_skills: self.education_skills,
+            tags,
+            chart_skills: self.chart_skills,
+            today,
+        })
+    }
+}
 
-    #[cfg(feature = "pdf")]
-    cv.to_pdf(Some(&tex_path))?;
+/// duration from a number of months
+fn months(months: u32) -> CVDuration {
+    CVDuration {
+        year: months / 12,
+        month: months % 12,
+    }
+}
 
+/// write the output to the file, or to the standard output if `-`
+fn write_output(output: &Path, content: &str) -> Result<()> {
+    if output == Path::new("-") {
+        io::stdout()
+            .write_all(content.as_bytes())
+            .context("cannot write to the standard output")?;
+    } else {
+        eprintln!("writing {}", output.display());
+        fs::write(output, content).with_context(|| format!("cannot write {}", output.display()))?;
+    }
     Ok(())
 }
+
+fn render(
+    source: &Source,
+    format: Format,
+    output: Option<PathBuf>,
+    latex: &LatexArgs,
+    filter: &FilterArgs,
+) -> Result<()> {
+    let mut cv = source.read()?;
+    cv.filter_entries(&filter.filter(source.today(), source.aliases()?));
+    if filter.sort_by_priority {
+        cv.sort_by_priority();
+    }
+    let extension = match format {
+        Format::Tex => "tex",
+        Format::Html => "html",
+        Format::Markdown => "md",
+        Format::JsonResume => "resume.json",
+    };
+    let output = output.unwrap_or_else(|| Path::new(&source.input).with_extension(extension));
+    match format {
+        Format::Tex => {
+            let options = latex.options(&cv, source.today())?;
+            write_output(&output, &cv.to_latex_with(&options)?)?;
+
+            #[cfg(feature = "pdf")]
+            if output != Path::new("-") {
+                cv.to_pdf_with(Some(&output), &options)?;
+            }
+        }
+        Format::Html => write_output(&output, &cv.to_html())?,
+        Format::Markdown => write_output(&output, &cv.to_markdown())?,
+        Format::JsonResume => {
+            let (resume, report) = cv.to_json_resume();
+            eprint!("{report}");
+            write_output(&output, &serde_json::to_string_pretty(&resume)?)?;
+        }
+    }
+    Ok(())
+}
+
+fn validate(source: &Source, max_gap: u32) -> Result<()> {
+    let cv = source.read()?;
+    let options = ValidationOptions {
+        max_gap: months(max_gap),
+        today: source.today(),
+    };
+    let issues = cv.validate(&options);
+    for issue in &issues {
+        println!("{issue}");
+    }
+    if !issues.is_empty() {
+        bail!("{} chronology issue(s) found", issues.len());
+    }
+    Ok(())
+}
+
+/// fail if the output is the input file, which would lose its comments
+fn refuse_input(source: &Source, output: &Path) -> Result<()> {
+    if output.canonicalize().ok() == Path::new(&source.input).canonicalize().ok() {
+        bail!("refusing to overwrite the input {}", source.input);
+    }
+    Ok(())
+}
+
+fn categorize(source: &Source, output: Option<PathBuf>) -> Result<()> {
+    let dictionary = TechnologyDictionary::builtin();
+    if let Some(output) = output {
+        refuse_input(source, &output)?;
+        let mut cv = source.read_as_is()?;
+        for suggestion in cv.categorize_skills(&dictionary) {
+            eprintln!("{suggestion}");
+        }
+        let content = cv.to_string_format(source.format()?)?;
+        return write_output(&output, &content);
+    }
+    let cv = source.read()?;
+    let suggestions = cv.suggest_categories(&dictionary);
+    for suggestion in &suggestions {
+        println!("{suggestion}");
+    }
+    if !suggestions.is_empty() {
+        bail!("{} skill(s) could be categorized", suggestions.len());
+    }
+    Ok(())
+}
+
+fn skills(
+    source: &Source,
+    format: ReportFormat,
+    sort: SkillSort,
+    categories: &[String],
+    education_skills: bool,
+) -> Result<()> {
+    let cv = source.read()?;
+    let mut report = cv.skill_report(&SkillOptions {
+        include_education: education_skills,
+        today: source.today(),
+    });
+    if !categories.is_empty() {
+        report.retain_categories(categories, &cv);
+    }
+    report.sort(sort, &cv);
+    let content = match format {
+        ReportFormat::Table => report.to_table(),
+        ReportFormat::Csv => report.to_csv(),
+        ReportFormat::Json => serde_json::to_string_pretty(&report)? + "\n",
+    };
+    write_output(Path::new("-"), &content)
+}
+
+fn convert(source: &Source, to: Option<SourceFormat>, output: Option<PathBuf>) -> Result<()> {
+    let format = to
+        .or_else(|| output.as_deref().and_then(SourceFormat::from_path))
+        .ok_or_else(|| anyhow!("no output format given, see --to"))?;
+    let output = output.unwrap_or_else(|| "-".into());
+    refuse_input(source, &output)?;
+    let cv = source.read_as_is()?;
+    write_output(&output, &cv.to_string_format(format)?)
+}
+
+fn run(args: Args) -> Result<()> {
+    match args.command {
+        Command::Render {
+            source,
+            format,
+            output,
+            latex,
+            filter,
+        } => render(&source, format, output, &latex, &filter),
+        Command::Validate { source, max_gap } => validate(&source, max_gap),
+        Command::Skills {
+            source,
+            format,
+            sort,
+            categories,
+            education_skills,
+        } => skills(&source, format, sort, &categories, education_skills),
+        Command::Convert { source, to, output } => convert(&source, to, output),
+        Command::Stats { source } => {
+            print!("{}", source.read()?.stats(source.today()));
+            Ok(())
+        }
+        Command::Timeline { source, width } => {
+            let options = TimelineOptions {
+                width,
+                today: source.today(),
+            };
+            print!("{}", source.read()?.to_timeline(&options));
+            Ok(())
+        }
+        Command::Categorize { source, output } => categorize(&source, output),
+        Command::Tags { source } => {
+            print!("{}", source.read()?.tag_names());
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuse_to_overwrite_input() {
+        let dir = std::env::temp_dir().join(format!("curriculum-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("cv.yaml");
+        fs::copy("tests/example4.yaml", &input).unwrap();
+        let content = fs::read_to_string(&input).unwrap();
+        let input = input.to_str().unwrap();
+        for command in ["convert", "categorize"] {
+            let args = Args::parse_from(["curriculum", command, input, "-o", input]);
+            let err = run(args).unwrap_err();
+            assert!(err
+                .to_string()
+                .starts_with("refusing to overwrite the input"));
+            assert_eq!(fs::read_to_string(input).unwrap(), content);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,355 @@
+//! Markdown rendering of the curriculum
+use crate::{CVEmail, CVEntry, CVLanguage, Curriculum, EntryDescription, PersonalData};
+use crate::{List, SkillCategory};
+
+/// escape characters having a meaning in Markdown inline text
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// percent-encode characters ending or breaking a Markdown link destination
+fn escape_url(url: &str) -> String {
+    let mut escaped = String::with_capacity(url.len());
+    for c in url.chars() {
+        if c.is_ascii_control() || matches!(c, ' ' | '"' | '(' | ')' | '<' | '>' | '\\') {
+            escaped.push_str(&format!("%{:02X}", c as u32));
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// transform text with multiple paragraph in Markdown
+fn format_long_text(text: &str) -> String {
+    escape_markdown(text).replace('\n', "  \n")
+}
+
+/// Get Markdown for small paragraph to be inserted in job description
+fn get_titled_description(title: &str, content: &str) -> String {
+    format!("**{title}**\n\n{content}")
+}
+
+/// Markdown table with a header line
+fn table(header: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut lines = Vec::new();
+    lines.push(format!("| {} |", header.join(" | ")));
+    lines.push(format!(
+        "|{}|",
+        header.iter().map(|_| "---").collect::<Vec<_>>().join("|")
+    ));
+    for row in rows {
+        lines.push(format!("| {} |", row.join(" | ")));
+    }
+    lines.join("\n")
+}
+
+impl Curriculum {
+    /// Generate the Markdown corresponding to the whole document
+    pub fn to_markdown(&self) -> String {
+        let mut output = Vec::new();
+        let categories = self.skill_categories();
+        output.push(self.personal_data.to_markdown());
+
+        output.push("## Education".into());
+        for edu in &self.education {
+            output.push(edu.to_markdown(3, &categories));
+        }
+
+        output.push("## Professional experience".into());
+        for experience in &self.experiences {
+            output.push(experience.to_markdown(3, &categories));
+        }
+
+        output.push("## Languages".into());
+        if !self.languages.is_empty() {
+            output.push(table(
+                &["Language", "Level", "Comment"],
+                self.languages.iter().map(|l| l.to_markdown()).collect(),
+            ));
+        }
+        let mut output = output.join("\n\n");
+        output.push('\n');
+        output
+    }
+}
+
+impl CVEntry {
+    /// Produce corresponding Markdown, the entry title being a heading of
+    /// the given level
+    fn to_markdown(&self, level: usize, categories: &[SkillCategory]) -> String {
+        let mut lines = Vec::new();
+        let title = [&self.degree, &self.institution]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| escape_markdown(&s.to_plain()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(
+            format!("{} {}", "#".repeat(level.min(6)), title)
+                .trim_end()
+                .into(),
+        );
+
+        let mut details = Vec::new();
+        let dates = self.get_dates();
+        if !dates.is_empty() {
+            details.push(dates.replace("--", "–"));
+        }
+        if let Some(city) = &self.city {
+            details.push(escape_markdown(&city.to_plain()));
+        }
+        if let Some(grade) = &self.grade {
+            details.push(escape_markdown(&grade.to_plain()));
+        }
+        if !details.is_empty() {
+            lines.push(format!("*{}*", details.join(" · ")));
+        }
+
+        if let Some(d) = &self.description {
+            let descr = d.to_markdown(categories);
+            if !descr.is_empty() {
+                lines.push(descr);
+            }
+        }
+        for subentry in &self.subentries {
+            lines.push(subentry.to_markdown(level + 1, categories));
+        }
+        lines.join("\n\n")
+    }
+}
+
+impl EntryDescription {
+    fn to_markdown(&self, categories: &[SkillCategory]) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        if !self.context.is_empty() {
+            lines.push(format_long_text(&self.context.to_plain()));
+        }
+        if !&self.achievements.is_empty() {
+            lines.push(get_titled_description(
+                "Achievements",
+                &List(self.achievements.clone()).to_markdown(),
+            ));
+        }
+        if !self.team.is_empty() {
+            lines.push(format!(
+                "**Team**: {}",
+                format_long_text(&self.team.to_plain())
+            ));
+        }
+        if !&self.tasks.is_empty() {
+            lines.push(get_titled_description(
+                "Tasks",
+                &List(self.tasks.clone()).to_markdown(),
+            ));
+        }
+        let skills = &self.extract_skills();
+        if !skills.is_empty() {
+            let rows = categories
+                .iter()
+                .filter_map(|category| {
+                    let list = skills.get(category.name.as_str())?;
+                    Some(vec![
+                        escape_markdown(category.display_name()),
+                        escape_markdown(&list.join(", ")),
+                    ])
+                })
+                .collect();
+            lines.push(get_titled_description(
+                "Technical environnement",
+                &table(&["Category", "Skills"], rows),
+            ));
+        }
+        lines.join("\n\n")
+    }
+}
+
+impl CVEmail {
+    fn to_markdown(&self) -> String {
+        let link = format!(
+            "[{}](mailto:{})",
+            escape_markdown(&self.mail),
+            escape_url(&self.mail)
+        );
+        if let Some(name) = &self.name {
+            format!("{}: {}", escape_markdown(name), link)
+        } else {
+            link
+        }
+    }
+}
+
+impl PersonalData {
+    fn to_markdown(&self) -> String {
+        let mut lines = Vec::new();
+        lines.push(format!("# {}", escape_markdown(&self.name)));
+        if let Some(title) = &self.title {
+            lines.push(format!("*{}*", escape_markdown(title)));
+        }
+        let mut contacts = Vec::new();
+        for t in &self.mobile {
+            contacts.push(format!("- mobile: {}", escape_markdown(t)));
+        }
+        for e in &self.email {
+            contacts.push(format!("- email: {}", e.to_markdown()));
+        }
+        // socials
+        let socials = [
+            ("github", "https://github.com/", &self.github),
+            ("gitlab", "https://gitlab.com/", &self.gitlab),
+            ("linkedin", "https://www.linkedin.com/in/", &self.linkedin),
+            ("twitter", "https://twitter.com/", &self.twitter),
+        ];
+        for (name, base, account) in socials {
+            if let Some(account) = account {
+                contacts.push(format!(
+                    "- {name}: [{}]({base}{})",
+                    escape_markdown(account),
+                    escape_url(account)
+                ));
+            }
+        }
+        for (n, u) in &self.webpage {
+            contacts.push(format!("- {}: <{}>", escape_markdown(n), escape_url(u)));
+        }
+        if !contacts.is_empty() {
+            lines.push(contacts.join("\n"));
+        }
+        lines.join("\n\n")
+    }
+}
+
+impl CVLanguage {
+    fn to_markdown(&self) -> Vec<String> {
+        vec![
+            escape_markdown(&self.language),
+            escape_markdown(&self.level),
+            escape_markdown(&self.comment),
+        ]
+    }
+}
+
+impl List {
+    fn to_markdown(&self) -> String {
+        self.0
+            .iter()
+            .map(|elt| format!("- {}", escape_markdown(&elt.to_plain())))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape() {
+        assert_eq!(escape_markdown("C# & *rust*"), "C\\# & \\*rust\\*");
+    }
+
+    #[test]
+    fn personal_data_links() {
+        let data = r#"
+        {
+            "name": "John Doe",
+            "email": [{"mail": "john (doe)@x.org"}],
+            "github": "john)doe",
+            "webpage": [["blog", "https://x.org/a b>c"]]
+        }"#;
+        let personal_data: PersonalData = serde_json::from_str(data).unwrap();
+        let md = personal_data.to_markdown();
+        assert!(md.contains("- email: [john (doe)@x.org](mailto:john%20%28doe%29@x.org)"));
+        assert!(md.contains("- github: [john)doe](https://github.com/john%29doe)"));
+        assert!(md.contains("- blog: <https://x.org/a%20b%3Ec>"));
+    }
+
+    #[test]
+    fn entry_markdown() {
+        let data = r#"
+        {
+            "beginning": "2019-07",
+            "end": "2023-11",
+            "degree": "developer",
+            "institution": "Campbell, Delgado and Parker",
+            "city": "Brussels",
+            "description": {
+                "context": "some super context",
+                "achievements": ["shipped"],
+                "team": "5 people",
+                "tasks": ["coding", "reviewing"],
+                "programming": ["rust", "python"],
+                "ci": ["git", "gitlab"]
+            },
+            "subentries": [
+                {
+                    "beginning": "2019-07",
+                    "end": "2020-07",
+                    "degree": "first mission"
+                }
+            ]
+        }
+        "#;
+        let entry: CVEntry = serde_json::from_str(data).unwrap();
+        let md = entry.to_markdown(3, &SkillCategory::defaults());
+        assert!(
+            md.starts_with("### developer, Campbell, Delgado and Parker\n\n*2019–2023 · Brussels*")
+        );
+        assert!(md.contains("**Achievements**\n\n- shipped"));
+        assert!(md.contains("**Team**: 5 people"));
+        assert!(md.contains("- reviewing"));
+        assert!(md.contains("| programming languages | rust, python |\n| CI/CD | git, gitlab |"));
+        assert!(md.contains("#### first mission\n\n*2019–2020*"));
+    }
+
+    #[test]
+    fn raw_latex() {
+        let data = r#"
+        {
+            "degree": {"latex": "\\LaTeX{} developer"},
+            "description": {
+                "context": {"latex": "first\\\\second"},
+                "tasks": [{"latex": "\\textbf{bold} \\& raw, 50\\%"}]
+            }
+        }
+        "#;
+        let entry: CVEntry = serde_json::from_str(data).unwrap();
+        let md = entry.to_markdown(3, &SkillCategory::defaults());
+        assert!(md.starts_with("### developer\n"));
+        assert!(md.contains("first  \nsecond"));
+        assert!(md.contains("- bold & raw, 50%"));
+        assert!(!md.contains('\\'));
+    }
+
+    #[test]
+    fn languages_table() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [],
+            "languages": [
+                {"language": "english", "level": "native"},
+                {"language": "french", "level": "fluent", "comment": "lived in Paris"}
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let md = cv.to_markdown();
+        assert!(md.starts_with("# Jessica\n\n## Education"));
+        assert!(
+            md.contains("| Language | Level | Comment |\n|---|---|---|\n| english | native |  |")
+        );
+        assert!(md.contains("| french | fluent | lived in Paris |"));
+    }
+}
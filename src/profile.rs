@@ -0,0 +1,141 @@
+//! Profiles enabling the conditional tags of the LaTeX output
+//!
+//! Each conditional block of the LaTeX output (`context`, `Tasks`,
+//! institutions...) is guarded by a tag. A profile decides which tags are
+//! enabled, so that the same curriculum gives short and long variants.
+use crate::source::read_path;
+use crate::{Curriculum, TagNames};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Tags enabled in the LaTeX output
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TagProfile {
+    /// whether tags not listed are enabled
+    #[serde(default = "enabled")]
+    pub default: bool,
+    /// tags or institutions enabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enable: Vec<String>,
+    /// tags or institutions disabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disable: Vec<String>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for TagProfile {
+    /// all tags enabled
+    fn default() -> Self {
+        Self {
+            default: true,
+            enable: Vec::new(),
+            disable: Vec::new(),
+        }
+    }
+}
+
+impl TagProfile {
+    /// Whether the tag is enabled, disabled tags prevailing over enabled ones
+    ///
+    /// Tags are compared case-insensitively, once normalized, so that
+    /// institutions can be written as in the curriculum.
+    /// Institutions sharing a normalized name are told apart by
+    /// [`TagProfile::is_enabled_with`].
+    /// ```
+    /// use curriculum::TagProfile;
+    ///
+    /// let profile = TagProfile {
+    ///     disable: vec!["tasks".into(), "Lee & Sons".into()],
+    ///     ..Default::default()
+    /// };
+    /// assert!(profile.is_enabled("context"));
+    /// assert!(!profile.is_enabled("Tasks"));
+    /// assert!(!profile.is_enabled("LeeSons"));
+    /// ```
+    pub fn is_enabled(&self, tag: &str) -> bool {
+        self.is_enabled_with(tag, &TagNames::default())
+    }
+
+    /// Whether the tag is enabled, institutions and entry tags being given
+    /// the tags of the curriculum, see [`Curriculum::tag_names`]
+    pub fn is_enabled_with(&self, tag: &str, names: &TagNames) -> bool {
+        let matches = |list: &[String]| {
+            list.iter()
+                .any(|name| names.tag(name).eq_ignore_ascii_case(tag))
+        };
+        !matches(&self.disable) && (self.default || matches(&self.enable))
+    }
+
+    /// Read named profiles from a JSON, YAML or TOML file
+    /// {name: profile}
+    pub fn read_profiles(path: &Path) -> Result<BTreeMap<String, TagProfile>> {
+        read_path(path)
+    }
+}
+
+impl Curriculum {
+    /// Profile defined in the curriculum
+    pub fn tag_profile(&self, name: &str) -> Option<&TagProfile> {
+        self.tag_profiles.get(name)
+    }
+}
+
+/// create latex corresponding to conditional tag compilation
+pub(crate) fn conditional_tags(
+    tags: BTreeSet<String>,
+    profile: &TagProfile,
+    names: &TagNames,
+) -> String {
+    tags.iter()
+        .map(|tag| {
+            if profile.is_enabled_with(tag, names) {
+                format!("\\newif\\if{tag}%\n%\\{tag}false\n\\{tag}true\n")
+            } else {
+                format!("\\newif\\if{tag}%\n%\\{tag}true\n\\{tag}false\n")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [
+                {
+                    "institution": "Lee & Sons",
+                    "description": {"context": "retail", "tasks": ["selling"]}
+                }
+            ],
+            "tag profiles": {
+                "short": {"disable": ["context", "tasks"]},
+                "minimal": {"default": false, "enable": ["firstpage"]}
+            }
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        assert!(cv.tag_profile("long").is_none());
+
+        let short = cv.tag_profile("short").unwrap();
+        let names = cv.tag_names();
+        let tex = conditional_tags(BTreeSet::from(["context".into()]), short, &names);
+        assert_eq!(tex, "\\newif\\ifcontext%\n%\\contexttrue\n\\contextfalse\n");
+
+        let minimal = cv.tag_profile("minimal").unwrap();
+        assert!(minimal.is_enabled("firstpage"));
+        assert!(!minimal.is_enabled("LeeSons"));
+        assert!(!minimal.is_enabled("skills"));
+    }
+}
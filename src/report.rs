@@ -0,0 +1,257 @@
+//! Report of the skills, as a table, CSV or JSON
+use crate::{CVDuration, Curriculum, SkillOptions};
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Experience in a skill, as a row of the report
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SkillRow {
+    /// display name of the category
+    pub category: String,
+    pub skill: String,
+    /// calendar time using the skill
+    pub duration: CVDuration,
+    /// sum of the durations of the entries using the skill
+    pub cumulative: CVDuration,
+    #[serde(rename = "last used")]
+    pub last_used: Option<i32>,
+}
+
+/// Order of the rows of the report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SkillSort {
+    /// categories in display order, most used skills first
+    #[default]
+    Category,
+    /// most used skills first
+    Duration,
+    /// alphabetical order of the skills
+    Name,
+}
+
+impl FromStr for SkillSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "category" => Ok(Self::Category),
+            "duration" => Ok(Self::Duration),
+            "name" => Ok(Self::Name),
+            _ => Err(format!(
+                "unknown sort {s} (expected category, duration or name)"
+            )),
+        }
+    }
+}
+
+/// Skills of a curriculum with their duration
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct SkillReport {
+    pub rows: Vec<SkillRow>,
+}
+
+/// quote the CSV field if needed
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into()
+    }
+}
+
+impl SkillReport {
+    /// Keep the skills of the categories, given by name or display name
+    pub fn retain_categories(&mut self, categories: &[String], cv: &Curriculum) {
+        let kept = cv
+            .skill_categories()
+            .into_iter()
+            .filter(|c| {
+                categories.iter().any(|name| {
+                    name.eq_ignore_ascii_case(&c.name)
+                        || name.eq_ignore_ascii_case(c.display_name())
+                })
+            })
+            .map(|c| c.display_name().to_string())
+            .collect::<Vec<_>>();
+        self.rows.retain(|row| kept.contains(&row.category));
+    }
+
+    /// Sort the rows, categories being in display order
+    pub fn sort(&mut self, sort: SkillSort, cv: &Curriculum) {
+        let categories = cv
+            .skill_categories()
+            .iter()
+            .map(|c| c.display_name().to_string())
+            .collect::<Vec<_>>();
+        let rank = |row: &SkillRow| categories.iter().position(|c| *c == row.category);
+        self.rows.sort_by(|r1, r2| {
+            let by_duration = r2
+                .duration
+                .cmp(&r1.duration)
+                .then(r2.cumulative.cmp(&r1.cumulative));
+            let by_name = r1
+                .skill
+                .to_lowercase()
+                .cmp(&r2.skill.to_lowercase())
+                .then(r1.skill.cmp(&r2.skill));
+            match sort {
+                SkillSort::Category => rank(r1).cmp(&rank(r2)).then(by_duration).then(by_name),
+                SkillSort::Duration => by_duration.then(by_name),
+                SkillSort::Name => by_name.then(rank(r1).cmp(&rank(r2))),
+            }
+        });
+    }
+
+    /// Table aligned for a terminal
+    pub fn to_table(&self) -> String {
+        let header = ["category", "skill", "duration", "cumulative", "last used"];
+        let mut table = vec![header.map(String::from)];
+        for row in &self.rows {
+            table.push([
+                row.category.clone(),
+                row.skill.clone(),
+                row.duration.to_string(),
+                row.cumulative.to_string(),
+                row.last_used.map(|y| y.to_string()).unwrap_or_default(),
+            ]);
+        }
+        let widths = (0..header.len())
+            .map(|i| {
+                table
+                    .iter()
+                    .map(|r| r[i].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        table
+            .iter()
+            .map(|row| {
+                let cells = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{cell:width$}"))
+                    .collect::<Vec<_>>();
+                format!("{}\n", cells.join("  ").trim_end())
+            })
+            .collect()
+    }
+
+    /// CSV with a header, durations in years and months
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec![
+            "category,skill,years,months,cumulative years,cumulative months,last used".to_string(),
+        ];
+        for row in &self.rows {
+            lines.push(format!(
+                "{},{},{},{},{},{},{}",
+                csv_field(&row.category),
+                csv_field(&row.skill),
+                row.duration.year,
+                row.duration.month,
+                row.cumulative.year,
+                row.cumulative.month,
+                row.last_used.map(|y| y.to_string()).unwrap_or_default(),
+            ));
+        }
+        lines.iter().map(|l| format!("{l}\n")).collect()
+    }
+}
+
+impl Curriculum {
+    /// Report of the skills, categories in display order, most used first
+    pub fn skill_report(&self, options: &SkillOptions) -> SkillReport {
+        let skills = self.get_skills_with(options);
+        let mut report = SkillReport::default();
+        for category in self.skill_categories() {
+            for (skill, experience) in skills.get(category.name.as_str()).into_iter().flatten() {
+                report.rows.push(SkillRow {
+                    category: category.display_name().into(),
+                    skill: skill.clone(),
+                    duration: experience.calendar.clone(),
+                    cumulative: experience.cumulative.clone(),
+                    last_used: experience.last_used,
+                });
+            }
+        }
+        report.sort(SkillSort::Category, self);
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn report() -> (Curriculum, SkillReport) {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [
+                {
+                    "beginning": "2015-01",
+                    "end": "2018-01",
+                    "description": {"programming": ["python", "C"], "ci": ["git"]}
+                },
+                {
+                    "beginning": "2020-01",
+                    "description": {"programming": ["rust"], "skills": {"other": ["Excel, VBA"]}}
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let report = cv.skill_report(&SkillOptions {
+            include_education: false,
+            today: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        });
+        (cv, report)
+    }
+
+    fn skills(report: &SkillReport) -> Vec<&str> {
+        report.rows.iter().map(|r| r.skill.as_str()).collect()
+    }
+
+    #[test]
+    fn skill_report() {
+        let (cv, mut report) = report();
+        assert_eq!(
+            skills(&report),
+            vec!["rust", "C", "python", "git", "Excel, VBA"]
+        );
+        assert_eq!(
+            report.to_table().lines().take(2).collect::<Vec<_>>(),
+            vec![
+                "category               skill       duration  cumulative  last used",
+                "programming languages  rust        4 years   4 years     2024",
+            ]
+        );
+        assert_eq!(
+            report.to_csv().lines().last(),
+            Some("other,\"Excel, VBA\",4,0,4,0,2024")
+        );
+
+        report.sort(SkillSort::Name, &cv);
+        assert_eq!(
+            skills(&report),
+            vec!["C", "Excel, VBA", "git", "python", "rust"]
+        );
+        report.sort(SkillSort::Duration, &cv);
+        assert_eq!(
+            skills(&report),
+            vec!["Excel, VBA", "rust", "C", "git", "python"]
+        );
+
+        report.retain_categories(&["CI/CD".into(), "other".into()], &cv);
+        assert_eq!(skills(&report), vec!["Excel, VBA", "git"]);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json[1]["duration"],
+            serde_json::json!({"year": 3, "month": 0})
+        );
+        assert_eq!(json[1]["last used"], 2018);
+    }
+}
@@ -0,0 +1,142 @@
+//! Reading the curriculum from JSON, YAML or TOML sources
+use crate::strict::{UnknownField, UnknownFields};
+use crate::Curriculum;
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Format of a curriculum source file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl SourceFormat {
+    /// Guess the format from the file extension
+    /// ```
+    /// use curriculum::SourceFormat;
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(SourceFormat::from_path(Path::new("cv.yml")), Some(SourceFormat::Yaml));
+    /// assert_eq!(SourceFormat::from_path(Path::new("cv.TOML")), Some(SourceFormat::Toml));
+    /// assert_eq!(SourceFormat::from_path(Path::new("cv.tex")), None);
+    /// ```
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        extension.parse().ok()
+    }
+
+    /// Read data other than a curriculum in this format
+    pub(crate) fn deserialize<T: DeserializeOwned>(self, content: &str) -> Result<T> {
+        Ok(match self {
+            Self::Json => serde_json::from_str(content)?,
+            Self::Yaml => serde_norway::from_str(content)?,
+            Self::Toml => toml::from_str(content)?,
+        })
+    }
+}
+
+/// Read data other than a curriculum from a file, the format being guessed
+/// from the file extension
+pub(crate) fn read_path<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let format = SourceFormat::from_path(path)
+        .ok_or_else(|| anyhow!("cannot guess format of {}", path.display()))?;
+    format.deserialize(&fs::read_to_string(path)?)
+}
+
+impl FromStr for SourceFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            "toml" => Ok(Self::Toml),
+            _ => Err(format!(
+                "unknown source format {s} (expected json, yaml or toml)"
+            )),
+        }
+    }
+}
+
+impl Curriculum {
+    /// Read curriculum from a string in the given format
+    ///
+    /// Unknown fields are ignored.
+    pub fn from_str_format(content: &str, format: SourceFormat) -> Result<Self> {
+        Ok(Self::from_str_checked(content, format)?.0)
+    }
+
+    /// Read curriculum from a string in the given format
+    ///
+    /// Unknown fields are ignored and returned alongside the curriculum.
+    pub fn from_str_checked(
+        content: &str,
+        format: SourceFormat,
+    ) -> Result<(Self, Vec<UnknownField>)> {
+        let mut unknown = Vec::new();
+        let mut callback = |path: serde_ignored::Path| unknown.push(UnknownField::new(&path));
+        let cv = match format {
+            SourceFormat::Json => {
+                let mut deserializer = serde_json::Deserializer::from_str(content);
+                let cv = serde_ignored::deserialize(&mut deserializer, &mut callback)?;
+                deserializer.end()?;
+                cv
+            }
+            SourceFormat::Yaml => serde_ignored::deserialize(
+                serde_norway::Deserializer::from_str(content),
+                &mut callback,
+            )?,
+            SourceFormat::Toml => {
+                serde_ignored::deserialize(toml::Deserializer::new(content), &mut callback)?
+            }
+        };
+        Ok((cv, unknown))
+    }
+
+    /// Read curriculum from a string in the given format
+    ///
+    /// Fails on unknown fields, the error listing them with a suggestion.
+    pub fn from_str_strict(content: &str, format: SourceFormat) -> Result<Self> {
+        let (cv, unknown) = Self::from_str_checked(content, format)?;
+        if unknown.is_empty() {
+            Ok(cv)
+        } else {
+            Err(UnknownFields(unknown).into())
+        }
+    }
+
+    /// Write curriculum to a string in the given format
+    pub fn to_string_format(&self, format: SourceFormat) -> Result<String> {
+        Ok(match format {
+            SourceFormat::Json => serde_json::to_string_pretty(self)?,
+            SourceFormat::Yaml => serde_norway::to_string(self)?,
+            SourceFormat::Toml => toml::to_string_pretty(self)?,
+        })
+    }
+
+    /// Read curriculum from a file
+    ///
+    /// If format is None, it is guessed from the file extension.
+    pub fn from_path(path: &Path, format: Option<SourceFormat>) -> Result<Self> {
+        Ok(Self::from_path_checked(path, format)?.0)
+    }
+
+    /// Read curriculum from a file, also returning the unknown fields
+    ///
+    /// If format is None, it is guessed from the file extension.
+    pub fn from_path_checked(
+        path: &Path,
+        format: Option<SourceFormat>,
+    ) -> Result<(Self, Vec<UnknownField>)> {
+        let format = format
+            .or_else(|| SourceFormat::from_path(path))
+            .ok_or_else(|| anyhow!("cannot guess format of {}", path.display()))?;
+        let content = fs::read_to_string(path)?;
+        Self::from_str_checked(&content, format)
+    }
+}
@@ -0,0 +1,132 @@
+//! Summary figures of a curriculum
+use crate::{covered_duration, CVDuration, CVEntry, Curriculum, SkillOptions};
+use chrono::{DateTime, Datelike, Utc};
+use std::fmt;
+
+/// Summary figures of a curriculum
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CurriculumStats {
+    /// number of experiences, subentries excluded
+    pub experiences: usize,
+    /// number of subentries of experiences, at any depth
+    pub subentries: usize,
+    /// number of education entries
+    pub education: usize,
+    /// calendar time covered by experiences
+    pub total_experience: CVDuration,
+    /// year the first experience began
+    pub first_year: Option<i32>,
+    /// number of distinct skills of experiences
+    pub skills: usize,
+    /// number of categories of these skills
+    pub categories: usize,
+    /// industries, in order of appearance
+    pub industries: Vec<String>,
+}
+
+impl fmt::Display for CurriculumStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "experiences: {} ({} subentries)",
+            self.experiences, self.subentries
+        )?;
+        writeln!(f, "education: {}", self.education)?;
+        writeln!(f, "total experience: {}", self.total_experience)?;
+        if let Some(year) = self.first_year {
+            writeln!(f, "first experience: {year}")?;
+        }
+        writeln!(
+            f,
+            "skills: {} in {} categories",
+            self.skills, self.categories
+        )?;
+        if !self.industries.is_empty() {
+            writeln!(f, "industries: {}", self.industries.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl CVEntry {
+    /// number of subentries, at any depth
+    fn count_subentries(&self) -> usize {
+        self.subentries
+            .iter()
+            .map(|sub| 1 + sub.count_subentries())
+            .sum()
+    }
+}
+
+impl Curriculum {
+    /// Summary figures, ongoing entries ending today
+    pub fn stats(&self, today: DateTime<Utc>) -> CurriculumStats {
+        let intervals = self
+            .experiences
+            .iter()
+            .filter_map(|xp| xp.span(today))
+            .collect();
+        let skills = self.get_skills_with(&SkillOptions {
+            include_education: false,
+            today,
+        });
+        CurriculumStats {
+            experiences: self.experiences.len(),
+            subentries: self.experiences.iter().map(CVEntry::count_subentries).sum(),
+            education: self.education.len(),
+            total_experience: CVDuration::from(covered_duration(intervals)),
+            first_year: self
+                .experiences
+                .iter()
+                .filter_map(|xp| xp.beginning?.date())
+                .min()
+                .map(|date| date.year()),
+            skills: skills.values().map(|list| list.len()).sum(),
+            categories: skills.len(),
+            industries: self.industries().iter().map(|i| i.to_string()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn stats() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [{"beginning": "2005", "end": "2010"}],
+            "experiences": [
+                {
+                    "beginning": "2012-01",
+                    "end": "2016-01",
+                    "industry": "energy",
+                    "description": {"programming": ["java", "python"]}
+                },
+                {
+                    "beginning": "2016-01",
+                    "industry": "health",
+                    "description": {"ci": ["jenkins"]},
+                    "subentries": [
+                        {"subentries": [{"description": {"programming": ["python"]}}]}
+                    ]
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let stats = cv.stats(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(
+            stats.to_string(),
+            "experiences: 2 (2 subentries)\n\
+             education: 1\n\
+             total experience: 12 years\n\
+             first experience: 2012\n\
+             skills: 3 in 2 categories\n\
+             industries: energy, health\n"
+        );
+    }
+}
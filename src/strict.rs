@@ -0,0 +1,263 @@
+//! Detection of unknown fields in curriculum sources
+//!
+//! Unknown fields are ignored by serde, which silently drops data
+//! written with a wrong key (e.g. `start` instead of `beginning`).
+use crate::SkillCategory;
+use crate::{CVEmail, CVEntry, CVLanguage, Curriculum, EntryDescription, PersonalData};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use std::error::Error;
+use std::fmt;
+
+/// usual names of fields, too far from the known name to be found by
+/// edit distance
+/// (unknown name, known name)
+const SYNONYMS: &[(&str, &str)] = &[
+    ("start", "beginning"),
+    ("begin", "beginning"),
+    ("from", "beginning"),
+    ("to", "end"),
+    ("until", "end"),
+    ("finish", "end"),
+    ("title", "degree"),
+    ("position", "degree"),
+    ("role", "degree"),
+    ("company", "institution"),
+    ("employer", "institution"),
+    ("school", "institution"),
+    ("university", "institution"),
+    ("location", "city"),
+    ("sector", "industry"),
+    ("phone", "mobile"),
+    ("mail", "email"),
+    ("website", "webpage"),
+    ("responsibilities", "tasks"),
+    ("highlights", "achievements"),
+    ("experience", "experiences"),
+    ("jobs", "experiences"),
+    ("work", "experiences"),
+];
+
+/// Field present in the source but not in the curriculum structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownField {
+    /// path of the field, e.g. `experiences[0].subentries[1].start`
+    pub path: String,
+    /// name of the field
+    pub field: String,
+    /// closest known field name
+    pub suggestion: Option<String>,
+}
+
+impl UnknownField {
+    pub(crate) fn new(path: &serde_ignored::Path) -> Self {
+        let mut keys = Vec::new();
+        let path_str = path_to_string(path, &mut keys);
+        let field = keys.pop().unwrap_or_default();
+        let known = known_fields(keys.last().map(String::as_str));
+        Self {
+            suggestion: suggest(&field, known).map(String::from),
+            path: path_str,
+            field,
+        }
+    }
+}
+
+impl fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: unknown field `{}`", self.path, self.field)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean `{suggestion}`?")?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by strict reading when the source has unknown fields
+#[derive(Debug)]
+pub struct UnknownFields(pub Vec<UnknownField>);
+
+impl fmt::Display for UnknownFields {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fields = self.0.iter().map(|u| u.to_string()).collect::<Vec<_>>();
+        write!(f, "{}", fields.join("\n"))
+    }
+}
+
+impl Error for UnknownFields {}
+
+/// build the path string like `experiences[0].start`
+/// keys receives the map keys of the path
+fn path_to_string(path: &serde_ignored::Path, keys: &mut Vec<String>) -> String {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => format!("{}[{index}]", path_to_string(parent, keys)),
+        Path::Map { parent, key } => {
+            let parent = path_to_string(parent, keys);
+            keys.push(key.clone());
+            if parent.is_empty() {
+                key.clone()
+            } else {
+                format!("{parent}.{key}")
+            }
+        }
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => path_to_string(parent, keys),
+    }
+}
+
+/// fields of the structure stored under the key parent
+fn known_fields(parent: Option<&str>) -> &'static [&'static str] {
+    match parent {
+        None => struct_fields::<Curriculum>(),
+        Some("personal data") => struct_fields::<PersonalData>(),
+        Some("email") => struct_fields::<CVEmail>(),
+        Some("education" | "experiences" | "subentries") => struct_fields::<CVEntry>(),
+        Some("description") => struct_fields::<EntryDescription>(),
+        Some("languages") => struct_fields::<CVLanguage>(),
+        Some("skill categories") => struct_fields::<SkillCategory>(),
+        Some(_) => &[],
+    }
+}
+
+/// closest known field name
+fn suggest(field: &str, known: &[&'static str]) -> Option<&'static str> {
+    let field = field.to_lowercase();
+    if let Some(&(_, name)) = SYNONYMS
+        .iter()
+        .find(|(synonym, name)| *synonym == field && known.contains(name))
+    {
+        return Some(name);
+    }
+    known
+        .iter()
+        .map(|&k| (levenshtein(&field, &k.to_lowercase()), k))
+        .filter(|&(distance, k)| distance <= (k.chars().count() / 3).max(2))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, k)| k)
+}
+
+/// edit distance between two strings
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// names of the fields of a structure, as seen by serde
+fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    struct Introspector<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for Introspector<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("not a structure"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("fields collected"))
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(Introspector(&mut fields));
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceFormat;
+
+    #[test]
+    fn fields_introspection() {
+        assert!(struct_fields::<CVEntry>().contains(&"beginning"));
+        assert!(struct_fields::<Curriculum>().contains(&"personal data"));
+        assert!(struct_fields::<EntryDescription>().contains(&"achievements"));
+    }
+
+    #[test]
+    fn suggestions() {
+        let entry = struct_fields::<CVEntry>();
+        assert_eq!(suggest("start", entry), Some("beginning"));
+        assert_eq!(suggest("Start", entry), Some("beginning"));
+        assert_eq!(suggest("instituion", entry), Some("institution"));
+        assert_eq!(suggest("sub_entries", entry), Some("subentries"));
+        assert_eq!(suggest("salary", entry), None);
+        let description = struct_fields::<EntryDescription>();
+        assert_eq!(suggest("acheivements", description), Some("achievements"));
+        assert_eq!(suggest("start", description), None);
+    }
+
+    #[test]
+    fn unknown_fields() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica", "phone": "+32 56 19 01"},
+            "education": [],
+            "experiences": [
+                {
+                    "start": "2022-10",
+                    "institution": "Brewer LLC",
+                    "description": {"contex": "some super context"},
+                    "subentries": [{"beginning": "2022-10", "finish": "2023-01"}]
+                }
+            ],
+            "languages": [{"language": "french", "levle": "native"}]
+        }
+        "#;
+        let (cv, unknown) = Curriculum::from_str_checked(data, SourceFormat::Json).unwrap();
+        assert!(cv.experiences[0].beginning.is_none());
+        let unknown: Vec<_> = unknown.iter().map(|u| u.to_string()).collect();
+        assert_eq!(
+            unknown,
+            vec![
+                "personal data.phone: unknown field `phone`, did you mean `mobile`?",
+                "experiences[0].start: unknown field `start`, did you mean `beginning`?",
+                "experiences[0].description.contex: unknown field `contex`, did you mean `context`?",
+                "experiences[0].subentries[0].finish: unknown field `finish`, did you mean `end`?",
+                "languages[0].levle: unknown field `levle`, did you mean `level`?",
+            ]
+        );
+        assert!(Curriculum::from_str_strict(data, SourceFormat::Json).is_err());
+    }
+
+    #[test]
+    fn strict_yaml() {
+        let data = "
+personal data:
+  name: Jessica
+education: []
+experiences: []
+experience: []
+";
+        let err = Curriculum::from_str_strict(data, SourceFormat::Yaml).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "experience: unknown field `experience`, did you mean `experiences`?"
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Names of the conditional tags of the LaTeX output
+//!
+//! Each tag `name` becomes a switch `\ifname` defined by `\newif`, so it
+//! must be made of letters only and must not redefine an existing
+//! conditional. Institutions and entry tags whose names would collapse to
+//! the same switch are disambiguated by a suffix, in order of appearance.
+use crate::{CVEntry, Curriculum};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use unidecode::unidecode;
+
+/// tags of the sections of the LaTeX output
+const SECTION_TAGS: &[&str] = &[
+    "context",
+    "Achievements",
+    "Team",
+    "Tasks",
+    "Technicalenvironnement",
+    "firstpage",
+    "skillchart",
+    "skills",
+    "timeline",
+];
+
+/// conditionals defined by TeX, e-TeX, pdfTeX and the classes, `\ifx`...
+const RESERVED_TAGS: &[&str] = &[
+    "absdim",
+    "absnum",
+    "case",
+    "cat",
+    "csname",
+    "defined",
+    "dim",
+    "eof",
+    "false",
+    "fontchar",
+    "hbox",
+    "hmode",
+    "incsname",
+    "inner",
+    "luatex",
+    "mmode",
+    "num",
+    "odd",
+    "pdf",
+    "pdfabsdim",
+    "pdfabsnum",
+    "pdfprimitive",
+    "pdftex",
+    "primitive",
+    "thenelse",
+    "true",
+    "vbox",
+    "vmode",
+    "void",
+    "vtex",
+    "x",
+    "xetex",
+];
+
+/// conditionals of etoolbox, loaded by moderncv, `\ifbool`...
+const ETOOLBOX_TAGS: &[&str] = &[
+    "blank",
+    "bool",
+    "boolexpe",
+    "boolexpr",
+    "cscounter",
+    "csdef",
+    "csdimen",
+    "csempty",
+    "csequal",
+    "cslength",
+    "csltxprotect",
+    "csmacro",
+    "csparam",
+    "csprefix",
+    "csprotected",
+    "csstrequal",
+    "csstring",
+    "csundef",
+    "csvoid",
+    "def",
+    "defcounter",
+    "defdimen",
+    "defempty",
+    "defequal",
+    "deflength",
+    "defltxprotect",
+    "defmacro",
+    "defparam",
+    "defprefix",
+    "defprotected",
+    "defstrequal",
+    "defstring",
+    "defvoid",
+    "dimcomp",
+    "dimequal",
+    "dimgreater",
+    "dimless",
+    "inlist",
+    "inlistcs",
+    "ltxcounter",
+    "numcomp",
+    "numequal",
+    "numgreater",
+    "numless",
+    "numodd",
+    "patchable",
+    "rmnum",
+    "strempty",
+    "strequal",
+    "toggle",
+    "undef",
+];
+
+const DIGITS: [&str; 10] = [
+    "Zero", "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine",
+];
+
+/// transform tag in a name made of ASCII letters, digits being spelled out
+pub(crate) fn normalize_tag(tag: &str) -> String {
+    let mut normalized = String::new();
+    for c in unidecode(tag).chars() {
+        if c.is_ascii_alphabetic() {
+            normalized.push(c);
+        } else if let Some(digit) = c.to_digit(10) {
+            normalized.push_str(DIGITS[digit as usize]);
+        }
+    }
+    if normalized.is_empty() {
+        normalized.push_str("untitled");
+    }
+    normalized
+}
+
+/// letters-only suffix of the nth duplicate: B, C, ..., Z, AA, AB...
+fn suffix(n: usize) -> String {
+    let mut n = n + 1;
+    let mut letters = Vec::new();
+    while n > 0 {
+        n -= 1;
+        letters.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+    letters.iter().rev().map(|&c| char::from(c)).collect()
+}
+
+/// Tags of the institutions and entry tags of a curriculum
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagNames {
+    /// {institution or entry tag: tag}
+    tags: BTreeMap<String, String>,
+}
+
+impl TagNames {
+    /// tag of the institution or entry tag, normalized if unknown
+    pub fn tag(&self, name: &str) -> String {
+        self.tags
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| normalize_tag(name))
+    }
+
+    /// (institution or entry tag, tag), sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.tags.iter().map(|(n, t)| (n.as_str(), t.as_str()))
+    }
+
+    /// give a tag to the name, unless it already has one
+    ///
+    /// `taken` holds the lowercase tags already given.
+    fn insert(&mut self, name: &str, taken: &mut HashSet<String>) {
+        if self.tags.contains_key(name) {
+            return;
+        }
+        let base = normalize_tag(name);
+        let tag = (0..)
+            .map(|n| match n {
+                0 => base.clone(),
+                n => format!("{base}{}", suffix(n)),
+            })
+            .find(|tag| !taken.contains(&tag.to_lowercase()))
+            .expect("suffixes should be unbounded");
+        taken.insert(tag.to_lowercase());
+        self.tags.insert(name.into(), tag);
+    }
+}
+
+impl fmt::Display for TagNames {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, tag) in self.iter() {
+            writeln!(f, "{name}: \\if{tag}")?;
+        }
+        Ok(())
+    }
+}
+
+impl CVEntry {
+    /// give tags to the institution and tags of the entry and its subentries
+    fn collect_tag_names(&self, names: &mut TagNames, taken: &mut HashSet<String>) {
+        names.insert(&self.institution.to_string(), taken);
+        for tag in &self.tags {
+            names.insert(tag, taken);
+        }
+        for subentry in &self.subentries {
+            subentry.collect_tag_names(names, taken);
+        }
+    }
+}
+
+impl Curriculum {
+    /// Tags of the institutions and entry tags, in the LaTeX output
+    pub fn tag_names(&self) -> TagNames {
+        let mut names = TagNames::default();
+        let mut taken = SECTION_TAGS
+            .iter()
+            .chain(RESERVED_TAGS)
+            .chain(ETOOLBOX_TAGS)
+            .map(|t| t.to_lowercase())
+            .collect();
+        for entry in self.education.iter().chain(&self.experiences) {
+            entry.collect_tag_names(&mut names, &mut taken);
+        }
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_tag("Lee & Sons"), "LeeSons");
+        assert_eq!(normalize_tag("Web3 (S.A.)"), "WebThreeSA");
+        assert_eq!(normalize_tag("Société Générale"), "SocieteGenerale");
+        assert_eq!(normalize_tag("東京"), "DongJing");
+        assert_eq!(normalize_tag("42"), "FourTwo");
+        assert_eq!(normalize_tag("---"), "untitled");
+        assert_eq!(suffix(1), "B");
+        assert_eq!(suffix(25), "Z");
+        assert_eq!(suffix(26), "AA");
+    }
+
+    #[test]
+    fn tag_names() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [{"institution": "A.B."}],
+            "experiences": [
+                {
+                    "institution": "AB",
+                    "tags": ["backend", "x", "blank", "Bool"],
+                    "subentries": [
+                        {"institution": "a-b", "tags": ["backend"]},
+                        {"institution": "Skills"}
+                    ]
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let names = cv.tag_names();
+        assert_eq!(
+            names.iter().collect::<Vec<_>>(),
+            vec![
+                ("A.B.", "AB"),
+                ("AB", "ABB"),
+                ("Bool", "BoolB"),
+                ("Skills", "SkillsB"),
+                ("a-b", "abC"),
+                ("backend", "backend"),
+                ("blank", "blankB"),
+                ("x", "xB"),
+            ]
+        );
+        assert_eq!(names.tag("AB"), "ABB");
+        assert_eq!(names.tag("Lee & Sons"), "LeeSons");
+        assert_eq!(names.to_string().lines().next(), Some("A.B.: \\ifAB"));
+    }
+}
@@ -0,0 +1,403 @@
+//! Timeline of the entries, drawn with ASCII characters or TikZ
+//!
+//! Each entry is a bar across the years, subentries being indented below
+//! their parent, so that gaps and overlaps are easy to spot. The LaTeX
+//! output draws the entries, without their subentries, on the first page.
+use crate::{escape_latex, reference_date, CVEntry, Curriculum, Interval, LatexOptions};
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use std::collections::BTreeSet;
+
+/// longest label, longer ones being truncated
+const MAX_LABEL: usize = 32;
+
+/// narrowest bar area
+const MIN_BARS: usize = 10;
+
+/// colors of the entry tags in the TikZ timeline, in order of appearance
+const TAG_COLORS: &[&str] = &[
+    "teal", "orange", "violet", "olive", "purple", "brown", "magenta", "cyan",
+];
+
+/// Options of the timeline
+#[derive(Debug, Clone)]
+pub struct TimelineOptions {
+    /// width of the timeline, in characters
+    pub width: usize,
+    /// date up to which ongoing entries are drawn
+    pub today: DateTime<Utc>,
+}
+
+impl Default for TimelineOptions {
+    fn default() -> Self {
+        Self {
+            width: 80,
+            today: reference_date(),
+        }
+    }
+}
+
+/// line of the timeline
+enum Row {
+    Section(&'static str),
+    Entry {
+        label: String,
+        span: Option<Interval>,
+        ongoing: bool,
+    },
+}
+
+impl CVEntry {
+    /// rows of the entry and its subentries
+    fn timeline_rows(
+        &self,
+        depth: usize,
+        parent: Option<Interval>,
+        today: DateTime<Utc>,
+        rows: &mut Vec<Row>,
+    ) {
+        let span = self.span_within(today, parent);
+        let ongoing = match self.end {
+            Some(end) => end.date().is_none(),
+            None => parent.is_none(),
+        };
+        let label = [&self.degree, &self.institution]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        rows.push(Row::Entry {
+            label: format!("{}{label}", "  ".repeat(depth)),
+            span: span.filter(|(b, e)| b <= e),
+            ongoing,
+        });
+        for subentry in &self.subentries {
+            subentry.timeline_rows(depth + 1, span, today, rows);
+        }
+    }
+}
+
+/// label padded or truncated to the width
+fn fit(label: &str, width: usize) -> String {
+    if label.chars().count() > width {
+        let truncated = label.chars().take(width - 3).collect::<String>();
+        format!("{truncated}...")
+    } else {
+        format!("{label:width$}")
+    }
+}
+
+impl Curriculum {
+    /// Draw education and experiences as bars across the years
+    ///
+    /// Bars are drawn `[===]`, ongoing entries ending with `>`.
+    pub fn to_timeline(&self, options: &TimelineOptions) -> String {
+        let mut rows = Vec::new();
+        for (section, entries) in [
+            ("Education", &self.education),
+            ("Experiences", &self.experiences),
+        ] {
+            if entries.is_empty() {
+                continue;
+            }
+            rows.push(Row::Section(section));
+            for entry in entries {
+                entry.timeline_rows(1, None, options.today, &mut rows);
+            }
+        }
+
+        let spans = rows.iter().filter_map(|row| match row {
+            Row::Entry { span, .. } => *span,
+            Row::Section(_) => None,
+        });
+        let first_year = spans.clone().map(|(b, _)| b.year()).min();
+        let last_year = spans.map(|(_, e)| e.year()).max();
+        let label_width = rows
+            .iter()
+            .map(|row| match row {
+                Row::Section(section) => section.len(),
+                Row::Entry { label, .. } => label.chars().count(),
+            })
+            .max()
+            .unwrap_or(0)
+            .min(MAX_LABEL);
+        let bar_width = options.width.saturating_sub(label_width + 1).max(MIN_BARS);
+
+        let mut lines: Vec<String> = Vec::new();
+        let column = match (first_year, last_year) {
+            (Some(first), Some(last)) => {
+                let year = |y| Utc.with_ymd_and_hms(y, 1, 1, 0, 0, 0).unwrap();
+                let (start, end) = (year(first), year(last + 1));
+                let days = (end - start).num_days();
+                let column = move |date: DateTime<Utc>| {
+                    let col = (date - start).num_days() * bar_width as i64 / days;
+                    (col.max(0) as usize).min(bar_width - 1)
+                };
+                // a year label every `step` years, so that labels do not touch
+                let years = (last - first + 1) as usize;
+                let step = (5 * years).div_ceil(bar_width).max(1);
+                let mut axis = vec![' '; bar_width];
+                let mut free = 0;
+                for y in (first..=last).step_by(step) {
+                    let col = column(year(y));
+                    let label = y.to_string();
+                    if col >= free && col + label.len() <= bar_width {
+                        axis[col..col + label.len()]
+                            .iter_mut()
+                            .zip(label.chars())
+                            .for_each(|(a, c)| *a = c);
+                        free = col + label.len() + 1;
+                    }
+                }
+                let axis = axis.into_iter().collect::<String>();
+                lines.push(format!("{} {}", fit("", label_width), axis.trim_end()));
+                Some(column)
+            }
+            _ => None,
+        };
+
+        for row in &rows {
+            let line = match row {
+                Row::Section(section) => section.to_string(),
+                Row::Entry {
+                    label,
+                    span,
+                    ongoing,
+                } => {
+                    let mut bar = vec![' '; bar_width];
+                    if let (Some((beginning, end)), Some(column)) = (span, &column) {
+                        let (b, e) = (column(*beginning), column(*end));
+                        bar[b..=e].fill('=');
+                        bar[b] = '[';
+                        bar[e] = if *ongoing { '>' } else { ']' };
+                    }
+                    let bar = bar.into_iter().collect::<String>();
+                    format!("{} {bar}", fit(label, label_width))
+                }
+            };
+            lines.push(line.trim_end().to_string());
+        }
+        lines.iter().map(|l| format!("{l}\n")).collect()
+    }
+}
+
+impl CVEntry {
+    /// degree and institution, in LaTeX
+    fn latex_label(&self) -> String {
+        [&self.degree, &self.institution]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_latex())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Curriculum {
+    /// create the TikZ timeline of education and experiences
+    ///
+    /// Bars are colored by the first tag of the entry, education being gray.
+    pub(crate) fn timeline_to_latex(
+        &self,
+        options: &LatexOptions,
+        tags: &mut BTreeSet<String>,
+    ) -> String {
+        let today = options.today;
+        let entries = self
+            .education
+            .iter()
+            .map(|e| (e, true))
+            .chain(self.experiences.iter().map(|e| (e, false)))
+            .filter_map(|(entry, education)| {
+                let span = entry.span(today).filter(|(b, e)| b <= e)?;
+                Some((entry, span, education))
+            })
+            .collect::<Vec<_>>();
+        let (Some(first), Some(last)) = (
+            entries.iter().map(|(_, (b, _), _)| b.year()).min(),
+            entries.iter().map(|(_, (_, e), _)| e.year()).max(),
+        ) else {
+            return "".into();
+        };
+        tags.insert("timeline".into());
+
+        let start = Utc.with_ymd_and_hms(first, 1, 1, 0, 0, 0).unwrap();
+        let years = f64::from(last + 1 - first);
+        let x = |date: DateTime<Utc>| (date - start).num_days() as f64 / 365.25;
+        let mut colors: Vec<&String> = Vec::new();
+        for (entry, _, _) in &entries {
+            if let Some(tag) = entry.tags.first() {
+                if !colors.contains(&tag) {
+                    colors.push(tag);
+                }
+            }
+        }
+        let color = |entry: &CVEntry, education: bool| match entry.tags.first() {
+            Some(tag) => {
+                let idx = colors.iter().position(|c| *c == tag).unwrap_or(0);
+                TAG_COLORS[idx % TAG_COLORS.len()]
+            }
+            None if education => "gray",
+            None => "color1",
+        };
+
+        let mut lines: Vec<String> = Vec::new();
+        lines.push("% ---- begin timeline".into());
+        lines.push("\\iftimeline%".into());
+        lines.push("\\begin{center}".into());
+        lines.push(format!(
+            "\\begin{{tikzpicture}}[x={:.4}\\linewidth, y=-0.6cm]",
+            0.95 / years
+        ));
+        for (row, (entry, (beginning, end), education)) in entries.iter().enumerate() {
+            let (x0, x1) = (x(*beginning), x(*end));
+            lines.push(format!(
+                "\\fill[{}] ({x0:.2},{row}) rectangle ({x1:.2},{row}.25);",
+                color(entry, *education)
+            ));
+            // labels of the entries of the second half end at their bar
+            let (anchor, at) = if x0 > years / 2.0 {
+                ("south east", x1)
+            } else {
+                ("south west", x0)
+            };
+            lines.push(format!(
+                "\\node[anchor={anchor}, inner sep=1pt, font=\\tiny] at ({at:.2},{row}) {{{}}};",
+                entry.latex_label()
+            ));
+        }
+        let axis = entries.len();
+        lines.push(format!("\\draw[gray] (0,{axis}) -- ({years},{axis});"));
+        let step = (years / 10.0).ceil().max(1.0) as usize;
+        for year in (first..=last + 1).step_by(step) {
+            let at = f64::from(year - first);
+            lines.push(format!(
+                "\\draw[gray] ({at},{axis}) -- ({at},{axis}.15) node[below, font=\\tiny] {{{year}}};"
+            ));
+        }
+        lines.push("\\end{tikzpicture}".into());
+        if !colors.is_empty() {
+            let legend = colors
+                .iter()
+                .enumerate()
+                .map(|(idx, tag)| {
+                    format!(
+                        "\\textcolor{{{}}}{{\\rule{{1ex}}{{1ex}}}}~{}",
+                        TAG_COLORS[idx % TAG_COLORS.len()],
+                        escape_latex(tag)
+                    )
+                })
+                .collect::<Vec<_>>();
+            lines.push(format!("\\\\{{\\tiny {}}}", legend.join("\\quad ")));
+        }
+        lines.push("\\end{center}".into());
+        lines.push("\\fi% ---- end   timeline".into());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeline() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [
+                {"beginning": "2010", "end": "2012", "degree": "Master", "institution": "Bennett-Wallace"}
+            ],
+            "experiences": [
+                {
+                    "beginning": "2012-01",
+                    "end": "2016-01",
+                    "institution": "Brewer LLC",
+                    "subentries": [{"beginning": "2014-01", "institution": "client"}]
+                },
+                {"beginning": "2017-01", "end": "present", "institution": "Lee & Sons"},
+                {"institution": "undated"}
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let options = TimelineOptions {
+            width: 51,
+            today: Utc.with_ymd_and_hms(2019, 12, 31, 0, 0, 0).unwrap(),
+        };
+        assert_eq!(
+            cv.to_timeline(&options),
+            "                          2010      2014      2018\n\
+             Education\n  \
+               Master, Bennett-Wallace [===]\n\
+             Experiences\n  \
+               Brewer LLC                  [=========]\n    \
+                 client                          [===]\n  \
+               Lee & Sons                               [======>\n  \
+               undated\n"
+        );
+    }
+
+    #[test]
+    fn five_digit_years() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [{"beginning": "9990", "end": "10003", "institution": "Lee & Sons"}]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let options = TimelineOptions {
+            width: 40,
+            today: Utc.with_ymd_and_hms(2019, 12, 31, 0, 0, 0).unwrap(),
+        };
+        let timeline = cv.to_timeline(&options);
+        let axis = timeline.lines().next().unwrap();
+        assert!(axis.chars().count() <= 40);
+        assert!(axis.trim_start().starts_with("9990"));
+        assert!(timeline.contains("Lee & Sons ["));
+    }
+
+    #[test]
+    fn tikz_timeline() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [{"beginning": "2010", "end": "2012", "degree": "Master"}],
+            "experiences": [
+                {"beginning": "2012", "end": "2016", "institution": "Lee & Sons", "tags": ["backend"]},
+                {"beginning": "2016", "end": "present", "institution": "Brewer LLC"}
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let options = LatexOptions {
+            today: Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap(),
+            ..Default::default()
+        };
+        let mut tags = BTreeSet::new();
+        let tex = cv.timeline_to_latex(&options, &mut tags);
+        let lines = tex.lines().collect::<Vec<_>>();
+        assert_eq!(lines[1], "\\iftimeline%");
+        assert_eq!(
+            lines[3],
+            "\\begin{tikzpicture}[x=0.0864\\linewidth, y=-0.6cm]"
+        );
+        assert_eq!(lines[4], "\\fill[gray] (0.00,0) rectangle (2.00,0.25);");
+        assert_eq!(lines[6], "\\fill[teal] (2.00,1) rectangle (6.00,1.25);");
+        assert_eq!(
+            lines[7],
+            "\\node[anchor=south west, inner sep=1pt, font=\\tiny] at (2.00,1) {Lee \\& Sons};"
+        );
+        assert_eq!(lines[8], "\\fill[color1] (6.00,2) rectangle (10.00,2.25);");
+        assert!(tex.contains("{\\tiny \\textcolor{teal}{\\rule{1ex}{1ex}}~backend}"));
+        assert_eq!(tags, BTreeSet::from(["timeline".into()]));
+
+        let empty: Curriculum = serde_json::from_str(
+            r#"{"personal data": {"name": "Jessica"}, "education": [], "experiences": []}"#,
+        )
+        .unwrap();
+        assert!(empty.timeline_to_latex(&options, &mut tags).is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+//! Chronology checks of curriculum entries
+use crate::{reference_date, CVDuration, CVEntry, Curriculum, Interval};
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// Kind of chronology issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    /// the entry ends before it begins
+    EndBeforeBeginning,
+    /// the entry begins after today
+    BeginningInFuture,
+    /// the entry ends after today
+    EndInFuture,
+    /// the subentry begins before or ends after its parent
+    OutsideParent,
+    /// the full-time entry overlaps another one, given by its path
+    Overlap(String),
+    /// no full-time entry during the duration before this entry
+    Gap(CVDuration),
+}
+
+/// Chronology issue of an entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// path of the entry, e.g. `experiences[2].subentries[0]`
+    pub path: String,
+    /// institution of the entry
+    pub institution: String,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.path)?;
+        if !self.institution.is_empty() {
+            write!(f, " ({})", self.institution)?;
+        }
+        match &self.kind {
+            IssueKind::EndBeforeBeginning => write!(f, ": ends before it begins"),
+            IssueKind::BeginningInFuture => write!(f, ": begins in the future"),
+            IssueKind::EndInFuture => write!(f, ": ends in the future"),
+            IssueKind::OutsideParent => write!(f, ": not within its parent's dates"),
+            IssueKind::Overlap(other) => write!(f, ": overlaps {other}"),
+            IssueKind::Gap(duration) => write!(f, ": preceded by a gap of {duration}"),
+        }
+    }
+}
+
+/// Options of the chronology checks
+#[derive(Debug, Clone)]
+pub struct ValidationOptions {
+    /// longest gap between experiences not reported
+    pub max_gap: CVDuration,
+    /// date of the checks
+    pub today: DateTime<Utc>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            max_gap: CVDuration { year: 0, month: 6 },
+            today: reference_date(),
+        }
+    }
+}
+
+/// entry with its path and resolved dates
+struct Span<'a> {
+    path: String,
+    entry: &'a CVEntry,
+    beginning: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+impl CVEntry {
+    /// check the entry and its subentries
+    ///
+    /// Missing dates are the ones of the parent, as in the skill aggregation.
+    fn validate(
+        &self,
+        path: &str,
+        parent: Option<Interval>,
+        options: &ValidationOptions,
+        issues: &mut Vec<ValidationIssue>,
+    ) {
+        let issue = |kind| ValidationIssue {
+            path: path.into(),
+            institution: self.institution.to_string(),
+            kind,
+        };
+        let beginning = self.beginning.and_then(|b| b.date());
+        let end = self.end.and_then(|e| e.date());
+        if let (Some(b), Some(e)) = (beginning, end) {
+            if e < b {
+                issues.push(issue(IssueKind::EndBeforeBeginning));
+            }
+        }
+        if beginning.is_some_and(|b| b > options.today) {
+            issues.push(issue(IssueKind::BeginningInFuture));
+        }
+        if end.is_some_and(|e| e > options.today) {
+            issues.push(issue(IssueKind::EndInFuture));
+        }
+
+        let span = self.span_within(options.today, parent);
+        for (idx, subentry) in self.subentries.iter().enumerate() {
+            let sub_path = format!("{path}.subentries[{idx}]");
+            let sub_span = subentry.span_within(options.today, span);
+            if let (Some((b, e)), Some((sub_b, sub_e))) = (span, sub_span) {
+                if sub_b < b || sub_e > e {
+                    issues.push(ValidationIssue {
+                        path: sub_path.clone(),
+                        institution: subentry.institution.to_string(),
+                        kind: IssueKind::OutsideParent,
+                    });
+                }
+            }
+            subentry.validate(&sub_path, span, options, issues);
+        }
+    }
+}
+
+impl Curriculum {
+    /// Check the chronology of entries
+    pub fn validate(&self, options: &ValidationOptions) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        for (section, entries) in [
+            ("education", &self.education),
+            ("experiences", &self.experiences),
+        ] {
+            for (idx, entry) in entries.iter().enumerate() {
+                entry.validate(&format!("{section}[{idx}]"), None, options, &mut issues);
+            }
+        }
+
+        // full-time experiences, entries with inconsistent dates being ignored
+        let mut spans = self
+            .experiences
+            .iter()
+            .enumerate()
+            .filter(|(_, xp)| !xp.part_time)
+            .filter_map(|(idx, entry)| {
+                let (beginning, end) = entry.span(options.today)?;
+                (beginning <= end).then(|| Span {
+                    path: format!("experiences[{idx}]"),
+                    entry,
+                    beginning,
+                    end,
+                })
+            })
+            .collect::<Vec<_>>();
+        spans.sort_by_key(|s| s.beginning);
+
+        for (i, span) in spans.iter().enumerate() {
+            for other in &spans[i + 1..] {
+                if other.beginning < span.end {
+                    issues.push(ValidationIssue {
+                        path: other.path.clone(),
+                        institution: other.entry.institution.to_string(),
+                        kind: IssueKind::Overlap(span.path.clone()),
+                    });
+                }
+            }
+        }
+
+        let mut covered_until: Option<DateTime<Utc>> = None;
+        for span in &spans {
+            if let Some(until) = covered_until {
+                let gap = CVDuration::from(span.beginning - until);
+                if gap > options.max_gap {
+                    issues.push(ValidationIssue {
+                        path: span.path.clone(),
+                        institution: span.entry.institution.to_string(),
+                        kind: IssueKind::Gap(gap),
+                    });
+                }
+            }
+            covered_until = covered_until.max(Some(span.end));
+        }
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn options() -> ValidationOptions {
+        ValidationOptions {
+            today: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn kinds(issues: &[ValidationIssue]) -> Vec<(&str, &IssueKind)> {
+        issues.iter().map(|i| (i.path.as_str(), &i.kind)).collect()
+    }
+
+    #[test]
+    fn entry_dates() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [
+                {"beginning": "1991-10-09", "end": "1988-11-02", "institution": "Bennett-Wallace"}
+            ],
+            "experiences": [
+                {
+                    "beginning": "2020-01",
+                    "end": "2025-01",
+                    "institution": "Brewer LLC",
+                    "subentries": [
+                        {"beginning": "2019-06", "end": "2021-01"},
+                        {"beginning": "2021-01", "end": "present"},
+                        {"beginning": "2024-06"}
+                    ]
+                },
+                {
+                    "beginning": "2015-01",
+                    "end": "2020-01",
+                    "institution": "Lee & Sons",
+                    "subentries": [
+                        {"beginning": "2016-01", "subentries": [{"beginning": "2017-01"}]}
+                    ]
+                }
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let issues = cv.validate(&options());
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                ("education[0]", &IssueKind::EndBeforeBeginning),
+                ("experiences[0]", &IssueKind::EndInFuture),
+                ("experiences[0].subentries[0]", &IssueKind::OutsideParent),
+                (
+                    "experiences[0].subentries[2]",
+                    &IssueKind::BeginningInFuture
+                ),
+            ]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "education[0] (Bennett-Wallace): ends before it begins"
+        );
+    }
+
+    #[test]
+    fn overlaps_and_gaps() {
+        let data = r#"
+        {
+            "personal data": {"name": "Jessica"},
+            "education": [],
+            "experiences": [
+                {"beginning": "2010-01", "end": "2012-01", "institution": "first"},
+                {"beginning": "2011-06", "end": "2013-01", "institution": "overlapping"},
+                {"beginning": "2012-06", "end": "2013-06", "institution": "teaching", "part time": true},
+                {"beginning": "2013-04", "end": "2016-01", "institution": "short gap"},
+                {"beginning": "2017-03", "institution": "long gap"}
+            ]
+        }
+        "#;
+        let cv: Curriculum = serde_json::from_str(data).unwrap();
+        let issues = cv.validate(&options());
+        assert_eq!(
+            kinds(&issues),
+            vec![
+                (
+                    "experiences[1]",
+                    &IssueKind::Overlap("experiences[0]".into())
+                ),
+                (
+                    "experiences[4]",
+                    &IssueKind::Gap(CVDuration { year: 1, month: 2 })
+                ),
+            ]
+        );
+        assert_eq!(
+            issues[1].to_string(),
+            "experiences[4] (long gap): preceded by a gap of 1 year 2 months"
+        );
+
+        let options = ValidationOptions {
+            max_gap: CVDuration { year: 2, month: 0 },
+            ..options()
+        };
+        assert_eq!(cv.validate(&options).len(), 1);
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::len_zero)] // kept as written in the original tests
+
 use anyhow::Result;
 use std::fs;
 use std::fs::File;
@@ -71,3 +73,126 @@ fn pdf_generation() -> Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn html_generation() -> Result<()> {
+    let mut treated = 0;
+    for entry in Path::new("./tests").read_dir().expect("read_dir failed?") {
+        if entry.as_ref().unwrap().path().extension().unwrap() != "json" {
+            eprintln!("ignoring {:?}", entry.as_ref());
+            continue;
+        }
+        let mut out_path = Path::new("/tmp").join(entry.as_ref().unwrap().file_name());
+        out_path.set_extension("html");
+        let content = fs::read_to_string(entry.unwrap().path())?;
+        let cv: curriculum::Curriculum = serde_json::from_str(&content)?;
+        let html_data = cv.to_html();
+        assert!(html_data.starts_with("<!DOCTYPE html>"));
+        assert!(html_data.contains("<style>"));
+        eprintln!("writing to {}", out_path.display());
+        fs::write(out_path.clone(), html_data)?;
+        assert!(out_path.is_file());
+        treated += 1;
+    }
+    assert!(treated > 0);
+    Ok(())
+}
+
+#[test]
+fn markdown_generation() -> Result<()> {
+    let mut treated = 0;
+    for entry in Path::new("./tests").read_dir().expect("read_dir failed?") {
+        if entry.as_ref().unwrap().path().extension().unwrap() != "json" {
+            eprintln!("ignoring {:?}", entry.as_ref());
+            continue;
+        }
+        let mut out_path = Path::new("/tmp").join(entry.as_ref().unwrap().file_name());
+        out_path.set_extension("md");
+        let content = fs::read_to_string(entry.unwrap().path())?;
+        let cv: curriculum::Curriculum = serde_json::from_str(&content)?;
+        let md_data = cv.to_markdown();
+        assert!(md_data.starts_with("# "));
+        assert!(md_data.contains("## Professional experience"));
+        eprintln!("writing to {}", out_path.display());
+        fs::write(out_path.clone(), md_data)?;
+        assert!(out_path.is_file());
+        treated += 1;
+    }
+    assert!(treated > 0);
+    Ok(())
+}
+
+#[test]
+fn yaml_and_toml_sources() -> Result<()> {
+    let yaml = curriculum::Curriculum::from_path(Path::new("./tests/example4.yaml"), None)?;
+    let toml = curriculum::Curriculum::from_path(Path::new("./tests/example4.toml"), None)?;
+    assert_eq!(serde_json::to_value(&yaml)?, serde_json::to_value(&toml)?);
+    assert!(!yaml.to_latex()?.is_empty());
+
+    let content = fs::read_to_string("./tests/example4.yaml")?;
+    assert!(
+        curriculum::Curriculum::from_str_format(&content, curriculum::SourceFormat::Toml).is_err()
+    );
+    assert!(curriculum::Curriculum::from_path(Path::new("./tests/example1.tex"), None).is_err());
+    Ok(())
+}
+
+#[test]
+fn strict_sources() -> Result<()> {
+    let mut treated = 0;
+    for dir in ["./tests", "./examples"] {
+        for entry in Path::new(dir).read_dir().expect("read_dir failed?") {
+            let path = entry?.path();
+            let Some(format) = curriculum::SourceFormat::from_path(&path) else {
+                eprintln!("ignoring {:?}", path);
+                continue;
+            };
+            let content = fs::read_to_string(&path)?;
+            curriculum::Curriculum::from_str_strict(&content, format)?;
+            treated += 1;
+        }
+    }
+    assert!(treated > 0);
+    Ok(())
+}
+
+#[test]
+fn format_round_trip() -> Result<()> {
+    use curriculum::SourceFormat;
+    let cv = curriculum::Curriculum::from_path(Path::new("./examples/example1.json"), None)?;
+    let expected = serde_json::to_value(&cv)?;
+    for format in [SourceFormat::Json, SourceFormat::Yaml, SourceFormat::Toml] {
+        let content = cv.to_string_format(format)?;
+        let read = curriculum::Curriculum::from_str_strict(&content, format)?;
+        assert_eq!(serde_json::to_value(&read)?, expected, "{format:?}");
+    }
+
+    // fields missing from the source are not written
+    let data = r#"{"personal data": {"name": "Jessica"}, "education": [{"institution": "ULB"}], "experiences": []}"#;
+    let cv = curriculum::Curriculum::from_str_format(data, SourceFormat::Json)?;
+    let yaml = cv.to_string_format(SourceFormat::Yaml)?;
+    assert!(!yaml.contains("null") && !yaml.contains("''"), "{yaml}");
+    Ok(())
+}
+
+#[test]
+fn reproducible_latex() -> Result<()> {
+    use chrono::{TimeZone, Utc};
+    let cv = curriculum::Curriculum::from_path(Path::new("./examples/example1.json"), None)?;
+    let options = curriculum::LatexOptions {
+        today: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        ..Default::default()
+    };
+    let tex = cv.to_latex_with(&options)?;
+    assert_eq!(tex, cv.to_latex_with(&options)?);
+
+    let tags = tex
+        .lines()
+        .filter_map(|l| l.strip_prefix("\\newif\\if"))
+        .collect::<Vec<_>>();
+    let mut sorted = tags.clone();
+    sorted.sort();
+    assert!(!tags.is_empty());
+    assert_eq!(tags, sorted);
+    Ok(())
+}